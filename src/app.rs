@@ -1,51 +1,116 @@
-use crate::direction::Direction;
-use crate::game::Game;
-use crate::settings::{AppSettings, GameSettings};
+use crate::textures::Textures;
 use piston_window::*;
-use std::{env::current_exe, path::PathBuf};
+use rust_battleship::{
+    BattleshipError, Connection, Difficulty, Direction, Game, GameSettings, Message, NetError,
+    PlacementOptions,
+};
+use std::{cmp, fs::File, io, path::PathBuf};
+
+/// The height of the area above the grid, which holds the player text and ship icons.
+pub const HEADER_HEIGHT: u32 = 60;
+
+/// The narrowest the window can be, so that the game over text fits.
+const MIN_WINDOW_WIDTH: u32 = 240;
+
+/// The largest the window can be.  Grid spaces are drawn smaller when a grid wouldn't fit.
+const MAX_WINDOW_SIZE: [u32; 2] = [1200, 900];
+
+/// The window's title, which is followed by the reason a ship couldn't be placed, if it
+/// couldn't.
+const WINDOW_TITLE: &str = "Battleship";
+
+/// How a player's fleet is placed when they ask for it to be placed for them.
+const AUTO_PLACEMENT: PlacementOptions = PlacementOptions {
+    no_touching: true,
+    prefer_edges: false,
+    spread_out: true,
+};
+
+pub struct AppSettings {
+    /// The size of each grid space, unless the grid is too large to fit in the window.
+    pub space_size: u32,
+    pub save_file: PathBuf,
+    pub replay_file: PathBuf,
+}
+
+impl AppSettings {
+    /// Returns the size of each grid space in a window that's the given number of grid spaces
+    /// wide and tall below the header, which is no larger than the settings' space size.
+    pub fn fitted_space_size(&self, spaces: [u32; 2]) -> u32 {
+        self.space_size
+            .min(MAX_WINDOW_SIZE[0] / spaces[0])
+            .min((MAX_WINDOW_SIZE[1] - HEADER_HEIGHT) / spaces[1])
+            .max(1)
+    }
+}
 
 pub struct App<'a> {
     window: PistonWindow,
     settings: &'a AppSettings,
     game: Game,
+    connection: Option<Connection>,
+    space_size: u32,
+    pending_shot: Option<[u8; 2]>,
+    /// The spaces chosen so far in the active player's volley, when they fire more than one shot
+    /// per turn.
+    volley: Vec<[u8; 2]>,
+    fleet_revealed: bool,
     turn_active: bool,
+    awaiting_handover: bool,
     turn_end_timer: f64,
     cpu_turn_timer: f64,
     mouse_cursor: [f64; 2],
-    grid_area: [u32; 4],
+    /// The areas of the shown player's own grid and the grid they're targeting.
+    grid_areas: [[u32; 4]; 2],
 }
 
 impl<'a> App<'a> {
-    pub fn new(settings: &AppSettings) -> App {
-        let game_settings = GameSettings::defaults();
-        let grid_area = [
-            settings.space_size,
-            settings.space_size * 3,
-            game_settings.spaces[0] as u32 * settings.space_size,
-            game_settings.spaces[1] as u32 * settings.space_size,
-        ];
-
+    pub fn new(
+        settings: &AppSettings,
+        game_settings: GameSettings,
+        connection: Option<Connection>,
+    ) -> App<'_> {
+        // Leave a space's width around and between the grids, and room for the fleet status
+        // below them.
+        let [columns, rows] = game_settings.spaces.map(|n| n as u32);
+        let space_size = settings.fitted_space_size([columns * 2 + 3, rows + 2]);
+        let grid_size = [columns * space_size, rows * space_size];
         let window_size = [
-            grid_area[2] + settings.space_size * 2,
-            grid_area[3] + settings.space_size * 4,
+            cmp::max(grid_size[0] * 2 + space_size * 3, MIN_WINDOW_WIDTH),
+            HEADER_HEIGHT + grid_size[1] + space_size * 2,
+        ];
+        let left = (window_size[0] - grid_size[0] * 2 - space_size) / 2;
+        let grid_areas = [
+            [left, HEADER_HEIGHT, grid_size[0], grid_size[1]],
+            [
+                left + grid_size[0] + space_size,
+                HEADER_HEIGHT,
+                grid_size[0],
+                grid_size[1],
+            ],
         ];
 
-        let window_title = "Battleship";
-        let window: PistonWindow = WindowSettings::new(window_title, window_size)
+        let window: PistonWindow = WindowSettings::new(WINDOW_TITLE, window_size)
             .exit_on_esc(true)
             .resizable(false)
             .build()
             .unwrap();
 
         App {
-            window: window,
-            settings: &settings,
+            window,
+            settings,
             game: Game::new(game_settings).unwrap(),
+            connection,
+            space_size,
+            pending_shot: None,
+            volley: Vec::new(),
+            fleet_revealed: false,
             turn_active: true,
+            awaiting_handover: false,
             turn_end_timer: 0.0,
             cpu_turn_timer: 0.0,
             mouse_cursor: [0.0; 2],
-            grid_area: grid_area,
+            grid_areas,
         }
     }
 
@@ -53,38 +118,17 @@ impl<'a> App<'a> {
         self.window.set_ups(60);
         self.window.set_max_fps(60);
 
-        // TODO set textures in a not terrible way
-        let assets_dir = Self::get_assets_dir(current_exe().unwrap()).unwrap();
-        let images_dir: PathBuf = assets_dir.join("images");
-        let mut space_textures = vec![];
-
-        for state in 0..3 {
-            let image_file = format!("gridspace-{}.png", state);
-            space_textures.push(self.get_texture(images_dir.join(&image_file)));
-        }
-
-        space_textures.push(self.get_texture(images_dir.join("shipspace.png")));
-
-        let grid_cursor_texture = self.get_texture(images_dir.join("grid-cursor.png"));
-
-        let mut ship_textures = vec![];
-        for ship_size in 2..6 {
-            let image_file = format!("ship-{}.png", ship_size);
-            ship_textures.push(self.get_texture(images_dir.join(&image_file)));
-        }
-
-        let player_text = [
-            self.get_texture(images_dir.join("player-1.png")),
-            self.get_texture(images_dir.join("player-2.png")),
-        ];
-
-        let game_over_text = [
-            self.get_texture(images_dir.join("game-over.png")),
-            self.get_texture(images_dir.join("wins.png")),
-        ];
+        let Textures {
+            spaces: space_textures,
+            grid_cursor: grid_cursor_texture,
+            player_text,
+            game_over_text,
+        } = Textures::load(&mut self.window);
 
         while let Some(e) = self.window.next() {
-            if let Some(p) = e.press_args() {
+            if let Some(p) = e.press_args().filter(|_| self.awaiting_handover) {
+                self.handover_press(p);
+            } else if let Some(p) = e.press_args() {
                 match p {
                     Button::Mouse(mouse::MouseButton::Left) => self.mouse_left_click(),
                     Button::Mouse(mouse::MouseButton::Right) => self.button_secondary(),
@@ -94,6 +138,14 @@ impl<'a> App<'a> {
                     Button::Keyboard(keyboard::Key::Down) => self.button_down(),
                     Button::Keyboard(keyboard::Key::Return) => self.button_primary(),
                     Button::Keyboard(keyboard::Key::Space) => self.button_secondary(),
+                    Button::Keyboard(keyboard::Key::D1) => self.select_difficulty(0),
+                    Button::Keyboard(keyboard::Key::D2) => self.select_difficulty(1),
+                    Button::Keyboard(keyboard::Key::D3) => self.select_difficulty(2),
+                    Button::Keyboard(keyboard::Key::S) => self.save_game(),
+                    Button::Keyboard(keyboard::Key::L) => self.load_game(),
+                    Button::Keyboard(keyboard::Key::Z) => self.undo_placement(),
+                    Button::Keyboard(keyboard::Key::Y) => self.redo_placement(),
+                    Button::Keyboard(keyboard::Key::A) => self.auto_place_fleet(),
                     _ => {}
                 }
             }
@@ -111,82 +163,154 @@ impl<'a> App<'a> {
                 let game_state_placement = self.game.is_state_placement();
                 let game_state_active = self.game.is_state_active();
                 let game_state_complete = self.game.is_state_complete();
-                let shown_player = match game_state_placement {
-                    true => current_player,
-                    false => self.game.inactive_player(),
+                let players = self.game.players();
+                let own_index = self.own_player_index();
+                let own_player = &players[own_index];
+                let sunk_ships = [self.game.sunk_ships(0), self.game.sunk_ships(1)];
+
+                let space_size = self.space_size as f64;
+                let [own_area, target_area] = self.grid_areas;
+                let texture_scale = space_size / space_textures[0].get_size().0 as f64;
+                let texture_transform = |c: Context, area: [u32; 4], pos: &[u8; 2]| {
+                    space_transform(c, area, space_size, pos).scale(texture_scale, texture_scale)
                 };
-
-                let space_size_u32 = self.settings.space_size as u32;
-                let grid_area = self.grid_area;
                 let window_size = self.window.size();
                 let turn_end_timer = self.turn_end_timer;
                 let game_winner = self.game.get_winner();
                 let game_turn = self.game.turn();
                 let turn_active = self.turn_active;
+                let fleet = &self.game.settings().ships;
+                let volley = &self.volley;
+                let awaiting_handover = self.awaiting_handover;
+                let has_cpu_player =
+                    self.game.active_player().is_cpu() || self.game.inactive_player().is_cpu();
+                let difficulty = self.game.settings().difficulty;
+                let difficulty_level = Difficulty::all()
+                    .iter()
+                    .position(|&d| d == difficulty)
+                    .unwrap();
 
                 self.window.draw_2d(&e, |c, g, _| {
-                    clear([0.6, 0.6, 1.0, 1.0], g);
-
-                    // Ship icons above grid
-                    for (i, ship) in shown_player.ships().iter().enumerate() {
-                        if ship.is_active() {
-                            let transform = c.transform.trans(
-                                (space_size_u32 * 2 * i as u32 + grid_area[0] * 2) as f64,
-                                30.0 as f64,
-                            );
-                            image(&ship_textures[i], transform, g);
-                        }
-                    }
+                    // Between hotseat turns, hide everything until the next player is ready.
+                    if awaiting_handover {
+                        clear([0.0, 0.0, 0.0, 1.0], g);
 
-                    // Grid spaces
-                    for space in shown_player.spaces() {
-                        let space_pos = space.pos();
+                        let player_text_size = player_text[game_turn].get_size();
                         let transform = c.transform.trans(
-                            (space_size_u32 * space_pos[0] as u32 + grid_area[0]) as f64,
-                            (space_size_u32 * space_pos[1] as u32 + grid_area[1]) as f64,
+                            (window_size.width - player_text_size.0 as f64) / 2.0,
+                            (window_size.height - player_text_size.1 as f64) / 2.0,
                         );
+                        image(&player_text[game_turn], transform, g);
+
+                        return;
+                    }
 
-                        // Only show ship locations during ship placement or if the
-                        // current player is computer-controlled.
-                        if shown_player.ship_is_in_space(space_pos)
-                            && (game_state_placement
-                                || (space.is_unchecked() && current_player.is_cpu()))
-                        {
-                            image(&space_textures[3], transform, g);
-                        } else {
-                            let space_state = if space.is_unchecked() {
-                                0
+                    clear([0.6, 0.6, 1.0, 1.0], g);
+
+                    // The shown player's own fleet on the left, and the grid they're targeting
+                    // on the right, each with the status of its fleet below it.
+                    for (area, index, show_ships) in [
+                        (own_area, own_index, true),
+                        (target_area, 1 - own_index, false),
+                    ] {
+                        for space in players[index].spaces() {
+                            let texture = if space.is_unchecked() || space.is_known_empty() {
+                                match show_ships && players[index].ship_is_in_space(space.pos()) {
+                                    true => 3,
+                                    false => 0,
+                                }
                             } else if space.is_empty() {
                                 1
                             } else {
                                 2
                             };
-                            image(&space_textures[space_state], transform, g);
+                            image(
+                                &space_textures[texture],
+                                texture_transform(c, area, space.pos()),
+                                g,
+                            );
+
+                            // Spaces known to be empty are shaded to set them apart from the
+                            // spaces that might still hide a ship.
+                            if space.is_known_empty() {
+                                rectangle(
+                                    [0.0, 0.0, 0.0, 0.3],
+                                    [0.0, 0.0, space_size, space_size],
+                                    space_transform(c, area, space_size, space.pos()),
+                                    g,
+                                );
+                            }
                         }
+
+                        draw_fleet_status(
+                            fleet,
+                            &sunk_ships[index],
+                            [
+                                area[0] as f64,
+                                (area[1] + area[3]) as f64 + space_size / 2.0,
+                                area[2] as f64,
+                                space_size / 2.0,
+                            ],
+                            c,
+                            g,
+                        );
                     }
 
                     // During ship placement, show the temporary position of the
                     // next ship to be placed.
                     if game_state_placement {
-                        if let Ok(ship) = shown_player.placement_ship() {
+                        if let Ok(ship) = own_player.placement_ship() {
                             for pos in ship.pos() {
-                                let transform = c.transform.trans(
-                                    (space_size_u32 * pos[0] as u32 + grid_area[0]) as f64,
-                                    (space_size_u32 * pos[1] as u32 + grid_area[1]) as f64,
-                                );
-                                image(&space_textures[3], transform, g);
+                                image(&space_textures[3], texture_transform(c, own_area, pos), g);
                             }
                         }
                     }
 
+                    // Before the game starts, show the CPU difficulty as a row of markers, one
+                    // filled in for each level.
+                    if game_state_placement && has_cpu_player {
+                        let marker_size = space_size / 2.0;
+
+                        for i in 0..Difficulty::all().len() {
+                            let alpha = match i <= difficulty_level {
+                                true => 0.8,
+                                false => 0.2,
+                            };
+                            rectangle(
+                                [0.0, 0.0, 0.0, alpha],
+                                [
+                                    own_area[0] as f64 + marker_size * 1.5 * i as f64,
+                                    own_area[1] as f64 - marker_size * 2.0,
+                                    marker_size,
+                                    marker_size,
+                                ],
+                                c.transform,
+                                g,
+                            );
+                        }
+                    }
+
+                    // Mark the spaces chosen so far in the player's volley.
+                    for pos in volley {
+                        rectangle(
+                            [1.0, 0.0, 0.0, 0.5],
+                            [0.0, 0.0, space_size, space_size],
+                            space_transform(c, target_area, space_size, pos),
+                            g,
+                        );
+                    }
+
                     // During the game, show the player's grid cursor.
-                    if game_state_active && turn_end_timer == 0.0 && !current_player.is_cpu() {
-                        let grid_cursor = current_player.grid_cursor();
-                        let transform = c.transform.trans(
-                            (space_size_u32 * grid_cursor[0] as u32 + grid_area[0]) as f64,
-                            (space_size_u32 * grid_cursor[1] as u32 + grid_area[1]) as f64,
+                    if game_state_active
+                        && turn_end_timer == 0.0
+                        && !current_player.is_cpu()
+                        && !current_player.is_remote()
+                    {
+                        image(
+                            &grid_cursor_texture,
+                            texture_transform(c, target_area, current_player.grid_cursor()),
+                            g,
                         );
-                        image(&grid_cursor_texture, transform, g);
                     }
 
                     // Current player text image
@@ -215,21 +339,24 @@ impl<'a> App<'a> {
                     }
 
                     // Game over content, to appear over the black rectangle.
-                    if turn_end_timer >= 1.5 && game_winner.is_some() {
-                        let winner = game_winner.unwrap();
+                    if let Some(winner) = game_winner.filter(|_| turn_end_timer >= 1.5) {
                         let game_over_text_size = game_over_text[0].get_size();
                         let wins_text_size = game_over_text[1].get_size();
                         let player_text_size = player_text[winner].get_size();
                         image(
                             &game_over_text[0],
-                            c.transform
-                                .trans((window_size.width - game_over_text_size.0 as f64) / 2.0, 2.0),
+                            c.transform.trans(
+                                (window_size.width - game_over_text_size.0 as f64) / 2.0,
+                                2.0,
+                            ),
                             g,
                         );
                         image(
                             &player_text[winner],
                             c.transform.trans(
-                                (window_size.width - (player_text_size.0 + wins_text_size.0 + 2) as f64) / 2.0,
+                                (window_size.width
+                                    - (player_text_size.0 + wins_text_size.0 + 2) as f64)
+                                    / 2.0,
                                 22.0,
                             ),
                             g,
@@ -237,7 +364,9 @@ impl<'a> App<'a> {
                         image(
                             &game_over_text[1],
                             c.transform.trans(
-                                (window_size.width + (player_text_size.0 - wins_text_size.0 + 2) as f64) / 2.0,
+                                (window_size.width
+                                    + (player_text_size.0 - wins_text_size.0 + 2) as f64)
+                                    / 2.0,
                                 22.0,
                             ),
                             g,
@@ -246,13 +375,194 @@ impl<'a> App<'a> {
                 });
             }
         }
+
+        // Keep an unfinished game so that it can be resumed later, or a finished game's replay so
+        // that it can be watched.  Network games can't be resumed without the other player.
+        if !self.game.is_state_complete() && !self.game.is_network() {
+            self.save_game();
+        } else if self.game.is_state_complete() {
+            self.save_replay();
+        }
+    }
+
+    /// Saves the game's replay to the replay file.
+    fn save_replay(&self) {
+        let result = File::create(&self.settings.replay_file).and_then(|file| {
+            self.game
+                .replay()
+                .save(io::BufWriter::new(file))
+                .map_err(io::Error::from)
+        });
+
+        if let Err(e) = result {
+            eprintln!(
+                "failed to save replay to {}: {}",
+                self.settings.replay_file.display(),
+                e
+            );
+        }
+    }
+
+    /// Saves the game to the save file, finishing any end-of-turn delay first so the next
+    /// player's turn is the one that's saved.
+    fn save_game(&mut self) {
+        if self.game.is_network() {
+            return;
+        }
+
+        if !self.turn_active && self.game.is_state_active() {
+            self.game.switch_active_player();
+            self.turn_end_timer = 0.0;
+            self.turn_active = true;
+        }
+
+        let result = File::create(&self.settings.save_file).and_then(|file| {
+            self.game
+                .save(io::BufWriter::new(file))
+                .map_err(io::Error::from)
+        });
+
+        if let Err(e) = result {
+            eprintln!(
+                "failed to save game to {}: {}",
+                self.settings.save_file.display(),
+                e
+            );
+        }
+    }
+
+    /// Replaces the current game with the one in the save file, if there is one.
+    fn load_game(&mut self) {
+        if self.game.is_network() {
+            return;
+        }
+
+        let result = File::open(&self.settings.save_file)
+            .and_then(|file| Game::load(io::BufReader::new(file)).map_err(io::Error::from));
+
+        match result {
+            Ok(game) => {
+                self.game = game;
+                self.volley.clear();
+                self.turn_active = true;
+                self.awaiting_handover = self.game.is_hotseat();
+                self.turn_end_timer = 0.0;
+                self.cpu_turn_timer = 0.0;
+            }
+            Err(e) => eprintln!(
+                "failed to load game from {}: {}",
+                self.settings.save_file.display(),
+                e
+            ),
+        }
+    }
+
+    /// Processes button presses while waiting for the next hotseat player to take the device.
+    fn handover_press(&mut self, button: Button) {
+        if let Button::Keyboard(keyboard::Key::Return) | Button::Mouse(mouse::MouseButton::Left) =
+            button
+        {
+            self.awaiting_handover = false;
+        }
+    }
+
+    /// Applies any messages received from a remote player.
+    ///
+    /// Messages are only applied while a turn is active, so a remote player's shot waits until
+    /// the end-of-turn delay for the local player's shot has finished.  Once the game is
+    /// complete, both players' fleets are revealed and the remote player's fleet is checked
+    /// against what they reported during the game.
+    fn receive_messages(&mut self) {
+        while self.turn_active || self.game.is_state_complete() {
+            let Some(connection) = &mut self.connection else {
+                return;
+            };
+
+            let mut verified = false;
+            let result = connection.try_recv().and_then(|message| match message {
+                None => Ok(false),
+                Some(Message::Ready(commitment)) => {
+                    self.game.set_remote_ready(commitment)?;
+                    Ok(true)
+                }
+                Some(Message::Shot(pos)) if self.game.active_player().is_remote() => {
+                    let outcome = self.game.select_space(&pos)?;
+                    connection.send(&Message::Result(outcome))?;
+                    self.turn_active = false;
+                    Ok(true)
+                }
+                Some(Message::Result(outcome)) if self.pending_shot.is_some() => {
+                    let pos = self.pending_shot.take().unwrap();
+                    self.game.record_remote_outcome(&pos, outcome)?;
+                    self.turn_active = false;
+                    Ok(true)
+                }
+                Some(Message::Reveal(reveal)) => {
+                    let mismatches = self.game.verify_remote_fleet(&reveal)?;
+
+                    if mismatches.is_empty() {
+                        println!("the other player's fleet matched everything they reported");
+                    }
+
+                    for mismatch in mismatches {
+                        eprintln!("warning: the other player may have cheated: {}", mismatch);
+                    }
+
+                    verified = true;
+                    Ok(false)
+                }
+                Some(message) => Err(NetError::UnexpectedMessage(message)),
+            });
+
+            // Nothing more is sent once the remote player's fleet has been checked.
+            if verified {
+                self.connection = None;
+            }
+
+            match result {
+                Ok(true) if self.game.is_state_complete() && !self.fleet_revealed => {
+                    let reveal = self.game.fleet_reveal().cloned();
+                    self.fleet_revealed = true;
+
+                    if let Some(reveal) = reveal {
+                        self.send_message(Message::Reveal(reveal));
+                    }
+                }
+                Ok(true) => {}
+                Ok(false) => return,
+                Err(e) => return self.network_error(e),
+            }
+        }
+    }
+
+    /// Sends `message` to the remote player.
+    fn send_message(&mut self, message: Message) {
+        if let Some(connection) = &mut self.connection {
+            if let Err(e) = connection.send(&message) {
+                self.network_error(e);
+            }
+        }
+    }
+
+    /// Disconnects from the remote player after a network error, closing the window if the game
+    /// hasn't finished.
+    fn network_error(&mut self, e: NetError) {
+        eprintln!("network game ended: {}", e);
+        self.connection = None;
+
+        if !self.game.is_state_complete() {
+            self.window.set_should_close(true);
+        }
     }
 
     fn update(&mut self, u: &UpdateArgs) {
-        if self.game.is_state_placement() && self.game.active_player_placed_all_ships() {
+        self.receive_messages();
+
+        if self.game.is_state_placement() && self.game.active_player_confirmed_fleet() {
             self.game.switch_active_player();
+            self.awaiting_handover = self.game.is_hotseat();
 
-            if self.game.active_player_placed_all_ships() {
+            if self.game.active_player_confirmed_fleet() {
                 // All ships have been placed; start the game.
                 // This will also set player 1 as active so no need to switch active player.
                 self.game
@@ -268,6 +578,7 @@ impl<'a> App<'a> {
                     self.game.switch_active_player();
                     self.turn_end_timer = 0.0;
                     self.turn_active = true;
+                    self.awaiting_handover = self.game.is_hotseat();
                 }
             }
 
@@ -276,9 +587,9 @@ impl<'a> App<'a> {
                 self.cpu_turn_timer += u.dt;
 
                 if self.cpu_turn_timer >= 1.0 {
-                    let cpu_space = self.game.suggested_check();
+                    let cpu_volley = self.game.suggested_salvo();
                     self.game
-                        .select_space(&cpu_space)
+                        .fire_salvo(&cpu_volley)
                         .expect("CPU player tried to select a checked space");
                     self.cpu_turn_timer = 0.0;
                     self.turn_active = false;
@@ -288,13 +599,53 @@ impl<'a> App<'a> {
     }
 
     fn primary_action(&mut self, grid_pos: &[u8; 2]) {
-        if self.game.is_player_placing_ship() && self.game.place_ship().is_err() {
-            // TODO: more specific error checking.
-            // For now, just assume it's the overlap error and ignore it.
+        if self.game.is_player_placing_ship() && self.game.active_player_placed_all_ships() {
+            // Every ship is placed, so the player is confirming their fleet.
+            match self.game.confirm_fleet() {
+                Ok(()) => {
+                    if let Some(commitment) = self.game.fleet_commitment() {
+                        self.send_message(Message::Ready(commitment));
+                    }
+                }
+                // The player is waiting for the remote player to finish placing their ships.
+                Err(BattleshipError::FleetConfirmed) => {}
+                Err(e) => panic!("failed to confirm fleet: {}", e),
+            }
+        } else if self.game.is_player_placing_ship() {
+            match self.game.place_ship() {
+                Ok(()) => self.window.set_title(WINDOW_TITLE.to_string()),
+                // The ship can't be placed where it is; say why, and leave it for the player to
+                // move.
+                Err(e @ (BattleshipError::Overlap | BattleshipError::Adjacent { .. })) => {
+                    self.window.set_title(format!("{} - {}", WINDOW_TITLE, e))
+                }
+                Err(e) => panic!("failed to place ship: {}", e),
+            }
         }
 
-        if self.game.is_player_selecting_space() && self.game.select_space(grid_pos).is_ok() {
-            self.turn_active = false;
+        if self.game.is_player_selecting_space() && self.turn_active && self.game.is_network() {
+            // The outcome is applied when the remote player's instance reports it.
+            if self.pending_shot.is_none() && self.game.target_view().space(grid_pos).is_unchecked()
+            {
+                self.pending_shot = Some(*grid_pos);
+                self.send_message(Message::Shot(*grid_pos));
+            }
+        } else if self.game.is_player_selecting_space() && self.turn_active {
+            // Selecting a chosen space again takes it out of the volley, and selecting a checked
+            // space does nothing; the player can select another one.
+            if let Some(i) = self.volley.iter().position(|pos| pos == grid_pos) {
+                self.volley.remove(i);
+            } else if self.game.target_view().space(grid_pos).is_unchecked() {
+                self.volley.push(*grid_pos);
+            }
+
+            if self.volley.len() == self.game.shots_per_turn() {
+                self.game
+                    .fire_salvo(&self.volley)
+                    .expect("failed to fire volley");
+                self.volley.clear();
+                self.turn_active = false;
+            }
         }
     }
 
@@ -320,28 +671,102 @@ impl<'a> App<'a> {
 
     /// Processes primary button presses according to the current program state.
     fn button_primary(&mut self) {
-        let grid_pos = self.game.active_player().grid_cursor().clone();
+        let grid_pos = *self.game.active_player().grid_cursor();
         self.primary_action(&grid_pos);
     }
 
     /// Processes secondary button presses according to the current program state.
     fn button_secondary(&mut self) {
         if self.game.is_player_placing_ship() {
-            self.game.rotate_ship().expect("failed to rotate ship");
+            match self.game.rotate_ship() {
+                // The ship is too long to fit on the grid the other way, or every ship is placed.
+                Ok(())
+                | Err(BattleshipError::OutOfBounds)
+                | Err(BattleshipError::NoPlacementShip) => {}
+                Err(e) => panic!("failed to rotate ship: {}", e),
+            }
+        }
+    }
+
+    /// Sets the CPU difficulty to the given level, if the game hasn't started yet.
+    fn select_difficulty(&mut self, level: usize) {
+        if self.game.is_state_placement() {
+            self.game
+                .set_difficulty(Difficulty::all()[level])
+                .expect("failed to set difficulty");
         }
     }
 
     /// Processes left mouse clicks according to the current program state.
+    ///
+    /// During ship placement, clicking a ship that has already been placed lifts it so that it
+    /// can be placed somewhere else.
     fn mouse_left_click(&mut self) {
         if let Some(grid_pos) = self.mouse_cursor_grid_position() {
-            self.primary_action(&grid_pos);
+            if self.game.is_player_placing_ship()
+                && self.game.active_player().ship_is_in_space(&grid_pos)
+            {
+                match self.game.lift_ship(&grid_pos) {
+                    // Another lifted ship has to be placed first, or the fleet is confirmed.
+                    Ok(())
+                    | Err(BattleshipError::ShipAlreadyLifted)
+                    | Err(BattleshipError::FleetConfirmed) => {}
+                    Err(e) => panic!("failed to lift ship: {}", e),
+                }
+            } else {
+                self.primary_action(&grid_pos);
+            }
+        }
+    }
+
+    /// Places the active player's whole fleet randomly, if they're placing ships.
+    fn auto_place_fleet(&mut self) {
+        if self.game.is_player_placing_ship() {
+            match self.game.auto_place_fleet(AUTO_PLACEMENT) {
+                // The fleet is confirmed, or there's no room for it with these options; the
+                // player can place their ships themselves.
+                Ok(())
+                | Err(BattleshipError::FleetConfirmed)
+                | Err(BattleshipError::FleetDoesNotFit) => {}
+                Err(e) => panic!("failed to place fleet: {}", e),
+            }
+        }
+    }
+
+    /// Undoes the active player's last ship placement or lift, if they're placing ships.
+    fn undo_placement(&mut self) {
+        if self.game.is_player_placing_ship() {
+            match self.game.undo_placement() {
+                Ok(())
+                | Err(BattleshipError::NothingToUndo)
+                | Err(BattleshipError::FleetConfirmed) => {}
+                Err(e) => panic!("failed to undo placement: {}", e),
+            }
+        }
+    }
+
+    /// Redoes the active player's last undone ship placement or lift, if they're placing ships.
+    fn redo_placement(&mut self) {
+        if self.game.is_player_placing_ship() {
+            match self.game.redo_placement() {
+                Ok(())
+                | Err(BattleshipError::NothingToRedo)
+                | Err(BattleshipError::FleetConfirmed) => {}
+                Err(e) => panic!("failed to redo placement: {}", e),
+            }
         }
     }
 
     /// Performs grid movement according to the current program state.
     fn movement(&mut self, direction: Direction) {
-        if self.game.is_player_placing_ship() && self.game.move_ship(direction).is_err() {
-            // TODO: more specific error checking.
+        if self.game.is_player_placing_ship() {
+            match self.game.move_ship(direction) {
+                // The ship is already at the edge of the grid, or every ship is placed.
+                Ok(())
+                | Err(BattleshipError::OutOfBounds)
+                | Err(BattleshipError::NoPlacementShip) => {}
+                Err(e) => panic!("failed to move ship: {}", e),
+            }
         }
 
         if self.game.is_player_selecting_space()
@@ -356,25 +781,26 @@ impl<'a> App<'a> {
     fn mouse_cursor_movement(&mut self, c: &[f64; 2]) {
         self.mouse_cursor = *c;
 
+        if self.awaiting_handover {
+            return;
+        }
+
         if let Some(grid_pos) = self.mouse_cursor_grid_position() {
-            if self.game.is_state_placement() {
+            if self.game.is_player_placing_ship() {
                 let player = self.game.active_player();
-                let ship_dir = player
-                    .placement_ship()
-                    .expect("failed to get player's placement ship")
-                    .dir();
-                // Subtract 1 from the ship count to not consider the placement ship itself.
-                let ship_count = player.ships().len() - 1;
-                let ship_len = self.game.settings().ships[ship_count];
-
-                if let Some(ship) = player.get_ship_position(grid_pos, ship_dir, ship_len) {
+
+                // Once every ship is placed, there's no placement ship until one is lifted.
+                let ship = player.placement_ship().ok().and_then(|ship| {
+                    player.get_ship_position(grid_pos, ship.dir(), ship.len() as u8)
+                });
+
+                if let Some(ship) = ship {
                     // `set_pos()` will return an error if the position was invalid.
                     self.game
                         .set_placement_ship(ship)
                         .expect("tried to set placement ship to invalid position");
                 }
-            } else if self.game.is_state_active()
-                && !self.game.active_player().is_cpu()
+            } else if self.game.is_player_selecting_space()
                 && self.game.set_grid_cursor(&grid_pos).is_err()
             {
                 // TODO: might be good to have some visual effect.
@@ -382,14 +808,36 @@ impl<'a> App<'a> {
         }
     }
 
+    /// Returns the index of the player whose own fleet is shown: the active player, unless
+    /// they're a CPU or remote player, so that a human player always sees their own fleet.
+    fn own_player_index(&self) -> usize {
+        let active = self.game.active_player();
+
+        match active.is_cpu() || active.is_remote() {
+            true => self.game.not_turn(),
+            false => self.game.turn(),
+        }
+    }
+
+    /// Returns the area of the grid that the player interacts with: their own grid while placing
+    /// ships, and otherwise the grid they're targeting.
+    fn input_grid_area(&self) -> [u32; 4] {
+        match self.game.is_state_placement() {
+            true => self.grid_areas[0],
+            false => self.grid_areas[1],
+        }
+    }
+
     /// Returns the grid coordinates of the mouse cursor position.
     fn mouse_cursor_grid_position(&self) -> Option<[u8; 2]> {
         if self.mouse_over_grid() {
-            let grid_area_f64 = [self.grid_area[0] as f64, self.grid_area[1] as f64];
+            let grid_area = self.input_grid_area();
+            let grid_area_f64 = [grid_area[0] as f64, grid_area[1] as f64];
+            let space_size = self.space_size as f64;
 
             Some([
-                ((self.mouse_cursor[0] - grid_area_f64[0]) / grid_area_f64[0]) as u8,
-                ((self.mouse_cursor[1] - grid_area_f64[1]) / grid_area_f64[0]) as u8,
+                ((self.mouse_cursor[0] - grid_area_f64[0]) / space_size) as u8,
+                ((self.mouse_cursor[1] - grid_area_f64[1]) / space_size) as u8,
             ])
         } else {
             None
@@ -397,34 +845,68 @@ impl<'a> App<'a> {
     }
 
     fn mouse_over_grid(&self) -> bool {
-        self.mouse_cursor[0] >= self.grid_area[0] as f64
-            && self.mouse_cursor[1] >= self.grid_area[1] as f64
-            && self.mouse_cursor[0] < (self.grid_area[0] + self.grid_area[2]) as f64
-            && self.mouse_cursor[1] < (self.grid_area[1] + self.grid_area[3]) as f64
-    }
+        let grid_area = self.input_grid_area();
 
-    /// Returns the texture from the file at the given path.
-    fn get_texture(&mut self, path: PathBuf) -> G2dTexture {
-        Texture::from_path(
-            &mut self.window.create_texture_context(),
-            path,
-            Flip::None,
-            &TextureSettings::new(),
-        )
-        .unwrap()
+        self.mouse_cursor[0] >= grid_area[0] as f64
+            && self.mouse_cursor[1] >= grid_area[1] as f64
+            && self.mouse_cursor[0] < (grid_area[0] + grid_area[2]) as f64
+            && self.mouse_cursor[1] < (grid_area[1] + grid_area[3]) as f64
     }
+}
 
-    /// Returns the assets directory, if it could be found.
-    fn get_assets_dir(mut dir: PathBuf) -> Result<PathBuf, &'static str> {
-        let mut result = None;
+/// Returns the transform to draw at the top left corner of the space at `pos`, in the grid
+/// drawn in `area` with spaces of the given size.
+fn space_transform(c: Context, area: [u32; 4], space_size: f64, pos: &[u8; 2]) -> math::Matrix2d {
+    c.transform.trans(
+        area[0] as f64 + space_size * pos[0] as f64,
+        area[1] as f64 + space_size * pos[1] as f64,
+    )
+}
 
-        while dir.pop() {
-            if dir.join("assets").exists() {
-                result = Some(dir.join("assets"));
-                break;
-            }
+/// Draws an icon for each ship in `fleet` in a row across `area`, with a block for each space
+/// of the ship.  The blocks are sized so that the whole fleet fits, up to the area's height.
+/// Ships that have been sunk are drawn faded and crossed out.
+fn draw_fleet_status(fleet: &[u8], sunk: &[bool], area: [f64; 4], c: Context, g: &mut G2d) {
+    let fleet_units = fleet.iter().map(|&len| len as usize + 1).sum::<usize>();
+    let block_size = f64::min(area[3], area[2] / (fleet_units - 1) as f64);
+    let mut icon_x = area[0];
+
+    for (&len, &sunk) in fleet.iter().zip(sunk) {
+        let color = match sunk {
+            true => [0.6, 0.1, 0.1, 0.5],
+            false => [0.3, 0.3, 0.3, 1.0],
+        };
+
+        for i in 0..len {
+            rectangle(
+                color,
+                [
+                    icon_x + block_size * i as f64,
+                    area[1],
+                    block_size * 0.8,
+                    block_size * 0.8,
+                ],
+                c.transform,
+                g,
+            );
+        }
+
+        if sunk {
+            let middle = area[1] + block_size * 0.4;
+            line(
+                [0.6, 0.1, 0.1, 1.0],
+                block_size * 0.1,
+                [
+                    icon_x,
+                    middle,
+                    icon_x + block_size * (len as f64 - 0.2),
+                    middle,
+                ],
+                c.transform,
+                g,
+            );
         }
 
-        result.ok_or("could not find assets directory")
+        icon_x += block_size * (len + 1) as f64;
     }
 }
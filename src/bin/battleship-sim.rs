@@ -0,0 +1,593 @@
+//! Plays games of Battleship between two CPU strategies without any frontend, and reports
+//! statistics on how well each strategy performed.
+
+#[path = "../parse.rs"]
+mod parse;
+
+use parse::{parse_adjacency, parse_difficulty, parse_fleet, parse_grid, parse_shots};
+use rust_battleship::{
+    AdjacencyRule, DefaultStrategy, Difficulty, ExternalStrategy, Game, GameSettings, ShotRule,
+    Strategy,
+};
+use serde::Serialize;
+use std::{env, process, process::Command, time::Duration};
+
+const USAGE: &str = "usage: battleship-sim [--games N] [--p1 PLAYER] [--p2 PLAYER] [--seed N] \
+                     [--bot-timeout MS] [--format text|csv|json] [--grid COLUMNSxROWS] \
+                     [--fleet LENGTHS] [--shots single|salvo|N] [--adjacency RULE]
+
+PLAYER is one of easy, normal or hard for the built-in CPU, or bot:COMMAND to start COMMAND as
+an external bot for each game.  The grid, fleet, shot and adjacency options take the same values
+as they do in rust-battleship.";
+
+struct Options {
+    games: usize,
+    levels: [Level; 2],
+    seed: u64,
+    bot_timeout: Duration,
+    format: Format,
+    grid: [u8; 2],
+    fleet: Vec<u8>,
+    shot_rule: ShotRule,
+    adjacency: AdjacencyRule,
+}
+
+/// Who controls one of the simulated players.
+enum Level {
+    Builtin(Difficulty),
+    /// An external bot, started with the given command line.
+    Bot(String),
+}
+
+enum Format {
+    Text,
+    Csv,
+    Json,
+}
+
+/// The result of one simulated game, from the point of view of the two strategies rather than
+/// the two players, since the strategies swap seats every game.
+struct GameRecord {
+    seed: u64,
+    first: usize,
+    winner: usize,
+    shots: [usize; 2],
+    /// For each strategy, the number of shots it had taken when it sunk each of the opponent's
+    /// ships.
+    sunk_at: [Vec<Option<usize>>; 2],
+}
+
+/// Statistics for one strategy over every simulated game.
+struct Summary {
+    wins: usize,
+    win_rate: f64,
+    shots_to_win: Vec<usize>,
+    /// The mean number of shots taken to sink each of the opponent's ships.
+    mean_time_to_sink: Vec<Option<f64>>,
+}
+
+/// The statistics printed with `--format json`.
+#[derive(Serialize)]
+struct Report<'a> {
+    games: usize,
+    seed: u64,
+    grid: [u8; 2],
+    ships: &'a [u8],
+    strategies: Vec<StrategyReport<'a>>,
+}
+
+#[derive(Serialize)]
+struct StrategyReport<'a> {
+    name: String,
+    level: &'a str,
+    wins: usize,
+    win_rate: f64,
+    shots_to_win: ShotsToWin,
+    mean_time_to_sink: &'a [Option<f64>],
+}
+
+#[derive(Serialize)]
+struct ShotsToWin {
+    mean: Option<f64>,
+    median: Option<usize>,
+    p10: Option<usize>,
+    p90: Option<usize>,
+}
+
+fn main() {
+    let options = parse_args(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("error: {}\n\n{}", e, USAGE);
+        process::exit(2);
+    });
+    let ships = &options.fleet;
+    let records = (0..options.games)
+        .map(|i| play(&options, i))
+        .collect::<Vec<_>>();
+    let summaries = [
+        summarise(&records, 0, ships.len()),
+        summarise(&records, 1, ships.len()),
+    ];
+
+    match options.format {
+        Format::Text => print_text(&options, ships, &summaries),
+        Format::Csv => print_csv(ships, &records),
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string(&report(&options, &summaries)).expect("failed to write JSON")
+        ),
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let defaults = GameSettings::defaults();
+    let mut options = Options {
+        games: 100,
+        levels: [
+            Level::Builtin(Difficulty::Normal),
+            Level::Builtin(Difficulty::Normal),
+        ],
+        seed: rand::random(),
+        bot_timeout: Duration::from_secs(1),
+        format: Format::Text,
+        grid: defaults.spaces,
+        fleet: defaults.ships,
+        shot_rule: defaults.shot_rule,
+        adjacency: defaults.adjacency,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+
+        match arg.as_str() {
+            "--games" => {
+                options.games = value()?
+                    .parse()
+                    .map_err(|_| "--games must be a number".to_string())?
+            }
+            "--p1" => options.levels[0] = parse_level(&value()?)?,
+            "--p2" => options.levels[1] = parse_level(&value()?)?,
+            "--seed" => {
+                options.seed = value()?
+                    .parse()
+                    .map_err(|_| "--seed must be a number".to_string())?
+            }
+            "--bot-timeout" => {
+                options.bot_timeout = Duration::from_millis(
+                    value()?
+                        .parse()
+                        .map_err(|_| "--bot-timeout must be a number".to_string())?,
+                )
+            }
+            "--format" => {
+                options.format = match value()?.as_str() {
+                    "text" => Format::Text,
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    other => return Err(format!("unknown format '{}'", other)),
+                }
+            }
+            "--grid" => options.grid = parse_grid(&value()?)?,
+            "--fleet" => options.fleet = parse_fleet(&value()?)?,
+            "--shots" => options.shot_rule = parse_shots(&value()?)?,
+            "--adjacency" => options.adjacency = parse_adjacency(&value()?)?,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            other => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+
+    options
+        .settings(None)
+        .validate()
+        .map_err(|e| format!("invalid grid and fleet: {}", e))?;
+
+    Ok(options)
+}
+
+fn parse_level(level: &str) -> Result<Level, String> {
+    match level.strip_prefix("bot:") {
+        Some(command) if !command.trim().is_empty() => Ok(Level::Bot(command.to_string())),
+        Some(_) => Err(format!("missing command for player '{}'", level)),
+        None => parse_difficulty(level).map(Level::Builtin),
+    }
+}
+
+impl Options {
+    /// Returns the settings for a game with the chosen grid, fleet and rules.
+    fn settings(&self, seed: Option<u64>) -> GameSettings {
+        GameSettings {
+            spaces: self.grid,
+            ships: self.fleet.clone(),
+            shot_rule: self.shot_rule,
+            adjacency: self.adjacency,
+            seed,
+            ..GameSettings::defaults()
+        }
+    }
+}
+
+/// Plays the `index`th game.  The strategies take turns to go first.
+fn play(options: &Options, index: usize) -> GameRecord {
+    let seed = options.seed.wrapping_add(index as u64);
+    let first = index % 2;
+    let settings = options.settings(Some(seed));
+    let strategy = |s: usize| -> Option<Box<dyn Strategy>> {
+        match &options.levels[s] {
+            Level::Builtin(difficulty) => Some(Box::new(DefaultStrategy::new(*difficulty))),
+            Level::Bot(command_line) => {
+                let mut words = command_line.split_whitespace();
+                let mut command = Command::new(words.next().unwrap());
+                command.args(words);
+
+                let bot =
+                    ExternalStrategy::spawn(command, options.bot_timeout, settings.difficulty)
+                        .unwrap_or_else(|e| {
+                            eprintln!("error: failed to start '{}': {}", command_line, e);
+                            process::exit(1);
+                        });
+
+                Some(Box::new(bot))
+            }
+        }
+    };
+    let strategies = [strategy(first), strategy(1 - first)];
+    let mut game = Game::with_strategies(settings, strategies).expect("failed to create game");
+    let ship_count = game.settings().ships.len();
+    let mut shots = [0; 2];
+    let mut sunk_at = [vec![None; ship_count], vec![None; ship_count]];
+
+    game.set_state_active().expect("failed to start game");
+
+    while !game.is_state_complete() {
+        let s = (game.turn() + first) % 2;
+        let volley = game.suggested_salvo();
+        let outcomes = game
+            .fire_salvo(&volley)
+            .expect("strategy selected an invalid volley");
+
+        // Sink times are counted in shots rather than turns, so every shot in the volley counts.
+        for outcome in outcomes {
+            shots[s] += 1;
+
+            if outcome.is_sunk() {
+                sunk_at[s][outcome.ship_index().unwrap()] = Some(shots[s]);
+            }
+        }
+
+        if !game.is_state_complete() {
+            game.switch_active_player();
+        }
+    }
+
+    GameRecord {
+        seed,
+        first,
+        winner: (game.get_winner().unwrap() + first) % 2,
+        shots,
+        sunk_at,
+    }
+}
+
+fn summarise(records: &[GameRecord], s: usize, ship_count: usize) -> Summary {
+    let mut shots_to_win = records
+        .iter()
+        .filter(|r| r.winner == s)
+        .map(|r| r.shots[s])
+        .collect::<Vec<_>>();
+    shots_to_win.sort_unstable();
+
+    let mean_time_to_sink = (0..ship_count)
+        .map(|ship| {
+            let times = records
+                .iter()
+                .filter_map(|r| r.sunk_at[s][ship])
+                .collect::<Vec<_>>();
+            mean(&times)
+        })
+        .collect();
+
+    Summary {
+        wins: shots_to_win.len(),
+        win_rate: shots_to_win.len() as f64 / records.len().max(1) as f64,
+        shots_to_win,
+        mean_time_to_sink,
+    }
+}
+
+fn mean(values: &[usize]) -> Option<f64> {
+    match values.is_empty() {
+        true => None,
+        false => Some(values.iter().sum::<usize>() as f64 / values.len() as f64),
+    }
+}
+
+/// Returns the nearest-rank percentile `p` of the sorted `values`.
+fn percentile(values: &[usize], p: f64) -> Option<usize> {
+    match values.is_empty() {
+        true => None,
+        false => {
+            let rank = (p / 100.0 * values.len() as f64).ceil() as usize;
+            Some(values[rank.clamp(1, values.len()) - 1])
+        }
+    }
+}
+
+fn level_name(level: &Level) -> &str {
+    match level {
+        Level::Builtin(Difficulty::Easy) => "easy",
+        Level::Builtin(Difficulty::Normal) => "normal",
+        Level::Builtin(Difficulty::Hard) => "hard",
+        Level::Bot(command) => command,
+    }
+}
+
+fn print_text(options: &Options, ships: &[u8], summaries: &[Summary; 2]) {
+    println!("games: {}, seed: {}", options.games, options.seed);
+
+    for (s, summary) in summaries.iter().enumerate() {
+        let shots = &summary.shots_to_win;
+        let show = |v: Option<usize>| v.map_or("-".to_string(), |v| v.to_string());
+
+        println!();
+        println!("p{} ({})", s + 1, level_name(&options.levels[s]));
+        println!(
+            "  wins:         {} ({:.1}%)",
+            summary.wins,
+            summary.win_rate * 100.0
+        );
+        println!(
+            "  shots to win: mean {}, median {}, p10 {}, p90 {}, min {}, max {}",
+            mean(shots).map_or("-".to_string(), |m| format!("{:.2}", m)),
+            show(percentile(shots, 50.0)),
+            show(percentile(shots, 10.0)),
+            show(percentile(shots, 90.0)),
+            show(shots.first().copied()),
+            show(shots.last().copied()),
+        );
+
+        for (ship, time) in summary.mean_time_to_sink.iter().enumerate() {
+            println!(
+                "  mean shots to sink ship {} (length {}): {}",
+                ship + 1,
+                ships[ship],
+                time.map_or("-".to_string(), |t| format!("{:.2}", t))
+            );
+        }
+    }
+}
+
+fn print_csv(ships: &[u8], records: &[GameRecord]) {
+    let mut header = vec!["game", "seed", "first", "winner", "p1_shots", "p2_shots"]
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    for s in 1..=2 {
+        for ship in 1..=ships.len() {
+            header.push(format!("p{}_sink_ship_{}", s, ship));
+        }
+    }
+
+    println!("{}", header.join(","));
+
+    for (i, record) in records.iter().enumerate() {
+        let mut row = vec![
+            i.to_string(),
+            record.seed.to_string(),
+            format!("p{}", record.first + 1),
+            format!("p{}", record.winner + 1),
+            record.shots[0].to_string(),
+            record.shots[1].to_string(),
+        ];
+
+        for sunk_at in &record.sunk_at {
+            row.extend(
+                sunk_at
+                    .iter()
+                    .map(|t| t.map_or(String::new(), |t| t.to_string())),
+            );
+        }
+
+        println!("{}", row.join(","));
+    }
+}
+
+fn report<'a>(options: &'a Options, summaries: &'a [Summary; 2]) -> Report<'a> {
+    let strategies = summaries
+        .iter()
+        .enumerate()
+        .map(|(s, summary)| {
+            let shots = &summary.shots_to_win;
+
+            StrategyReport {
+                name: format!("p{}", s + 1),
+                level: level_name(&options.levels[s]),
+                wins: summary.wins,
+                win_rate: summary.win_rate,
+                shots_to_win: ShotsToWin {
+                    mean: mean(shots),
+                    median: percentile(shots, 50.0),
+                    p10: percentile(shots, 10.0),
+                    p90: percentile(shots, 90.0),
+                },
+                mean_time_to_sink: &summary.mean_time_to_sink,
+            }
+        })
+        .collect();
+
+    Report {
+        games: options.games,
+        seed: options.seed,
+        grid: options.grid,
+        ships: &options.fleet,
+        strategies,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Options, String> {
+        parse_args(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn parse() {
+        let options = args(&[
+            "--games",
+            "5",
+            "--p1",
+            "easy",
+            "--p2",
+            "bot:./bot --fast",
+            "--seed",
+            "7",
+            "--format",
+            "csv",
+        ])
+        .unwrap();
+
+        assert_eq!(options.games, 5);
+        assert_eq!(options.seed, 7);
+        assert!(matches!(
+            options.levels[0],
+            Level::Builtin(Difficulty::Easy)
+        ));
+        assert_eq!(level_name(&options.levels[1]), "./bot --fast");
+        assert!(matches!(options.format, Format::Csv));
+
+        assert!(args(&["--games"]).is_err());
+        assert!(args(&["--games", "many"]).is_err());
+        assert!(args(&["--p1", "impossible"]).is_err());
+        assert!(args(&["--p2", "bot: "]).is_err());
+        assert!(args(&["--format", "xml"]).is_err());
+        assert!(args(&["--grid", "10"]).is_err());
+        assert!(args(&["--grid", "4x4", "--fleet", "5"]).is_err());
+        assert!(args(&["--shots", "0"]).is_err());
+        assert!(args(&["--verbose"]).is_err());
+    }
+
+    #[test]
+    fn settings() {
+        let options = args(&[
+            "--grid",
+            "8x6",
+            "--fleet",
+            "4,3,2",
+            "--shots",
+            "salvo",
+            "--adjacency",
+            "no-touch",
+        ])
+        .unwrap();
+        let settings = options.settings(Some(9));
+
+        assert_eq!(settings.spaces, [8, 6]);
+        assert_eq!(settings.ships, [4, 3, 2]);
+        assert_eq!(settings.shot_rule, ShotRule::Salvo);
+        assert_eq!(settings.adjacency, AdjacencyRule::NoTouchIncludingDiagonals);
+        assert_eq!(settings.seed, Some(9));
+
+        // Every shot of every volley is counted.
+        let record = play(&options, 0);
+        let winner = record.winner;
+        assert!(record.sunk_at[winner].iter().all(|t| t.is_some()));
+        assert!(record.shots[winner] >= 9);
+        assert!(record.shots[winner] <= 48);
+    }
+
+    #[test]
+    fn json() {
+        let options = args(&["--games", "2", "--seed", "1", "--p2", "bot:./bot \"x\""]).unwrap();
+        let summaries = [
+            Summary {
+                wins: 2,
+                win_rate: 1.0,
+                shots_to_win: vec![40, 50],
+                mean_time_to_sink: vec![Some(10.0), None, None, None, None],
+            },
+            Summary {
+                wins: 0,
+                win_rate: 0.0,
+                shots_to_win: vec![],
+                mean_time_to_sink: vec![None; 5],
+            },
+        ];
+        let json = serde_json::to_value(report(&options, &summaries)).unwrap();
+
+        assert_eq!(json["games"], 2);
+        assert_eq!(json["grid"], serde_json::json!([10, 10]));
+        assert_eq!(
+            json["ships"],
+            serde_json::json!(GameSettings::defaults().ships)
+        );
+        assert_eq!(json["strategies"][0]["shots_to_win"]["mean"], 45.0);
+        assert_eq!(json["strategies"][0]["shots_to_win"]["median"], 40);
+        assert_eq!(json["strategies"][0]["mean_time_to_sink"][0], 10.0);
+        assert!(json["strategies"][0]["mean_time_to_sink"][1].is_null());
+        assert_eq!(json["strategies"][1]["level"], "./bot \"x\"");
+        assert!(json["strategies"][1]["shots_to_win"]["median"].is_null());
+    }
+
+    #[test]
+    fn summary() {
+        let record = |winner, shots, sunk_at| GameRecord {
+            seed: 0,
+            first: 0,
+            winner,
+            shots,
+            sunk_at,
+        };
+        let records = [
+            record(0, [20, 18], [vec![Some(4), Some(20)], vec![Some(6), None]]),
+            record(1, [30, 25], [vec![Some(8), None], vec![Some(10), Some(25)]]),
+            record(0, [40, 39], [vec![Some(12), Some(40)], vec![None, None]]),
+        ];
+        let summary = summarise(&records, 0, 2);
+
+        assert_eq!(summary.wins, 2);
+        assert!((summary.win_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(summary.shots_to_win, [20, 40]);
+        assert_eq!(summary.mean_time_to_sink, [Some(8.0), Some(30.0)]);
+        assert_eq!(summarise(&[], 1, 2).win_rate, 0.0);
+    }
+
+    #[test]
+    fn statistics() {
+        assert_eq!(mean(&[]), None);
+        assert_eq!(mean(&[1, 2, 6]), Some(3.0));
+
+        let values = (1..=10).collect::<Vec<usize>>();
+        assert_eq!(percentile(&[], 50.0), None);
+        assert_eq!(percentile(&values, 0.0), Some(1));
+        assert_eq!(percentile(&values, 10.0), Some(1));
+        assert_eq!(percentile(&values, 50.0), Some(5));
+        assert_eq!(percentile(&values, 90.0), Some(9));
+        assert_eq!(percentile(&values, 100.0), Some(10));
+    }
+
+    #[test]
+    fn seeded_games() {
+        let options = args(&["--seed", "3"]).unwrap();
+        let records = [play(&options, 1), play(&options, 1)];
+
+        // The same seed plays the same game, with the second strategy going first.
+        assert_eq!(records[0].seed, 4);
+        assert_eq!(records[0].first, 1);
+        assert_eq!(records[0].winner, records[1].winner);
+        assert_eq!(records[0].shots, records[1].shots);
+        assert_eq!(records[0].sunk_at, records[1].sunk_at);
+
+        // The winner sunk every ship, on their last shot at the latest.
+        let winner = records[0].winner;
+        let sunk_at = &records[0].sunk_at[winner];
+        assert!(sunk_at.iter().all(|t| t.is_some()));
+        assert_eq!(
+            sunk_at.iter().max().unwrap(),
+            &Some(records[0].shots[winner])
+        );
+    }
+}
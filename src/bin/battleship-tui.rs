@@ -0,0 +1,705 @@
+//! Plays Battleship against a CPU player in the terminal, for when there's no window to draw in.
+
+// The settings are read in the same way as the window's, including some that only the window
+// uses.
+#[allow(dead_code)]
+#[path = "../config.rs"]
+mod config;
+#[path = "../parse.rs"]
+mod parse;
+
+use config::{Config, Launch};
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute, queue,
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor},
+    terminal::{self, ClearType},
+};
+use rust_battleship::{
+    BattleshipError, Difficulty, Direction, Game, GameMode, GameSettings, PlacementOptions, Player,
+    ShotOutcome, Space, TargetView,
+};
+use std::{
+    env,
+    io::{self, Write},
+    process,
+    time::{Duration, Instant},
+};
+
+/// How long a shot's outcome is shown before the other player's turn starts.
+const TURN_END_DELAY: Duration = Duration::from_millis(750);
+
+/// How long the CPU player takes to choose a space.
+const CPU_TURN_DELAY: Duration = Duration::from_millis(1000);
+
+/// The width of each grid, in terminal columns, is this many times the number of grid columns.
+const CELL_WIDTH: u16 = 2;
+
+/// The human player.
+const HUMAN: usize = 0;
+
+/// The number of columns in the widest grid that can be played, so that every column name is a
+/// single letter.
+const MAX_COLUMNS: u8 = 26;
+
+/// How the human player's fleet is placed when they ask for it to be placed for them.
+const AUTO_PLACEMENT: PlacementOptions = PlacementOptions {
+    no_touching: true,
+    prefer_edges: false,
+    spread_out: true,
+};
+
+struct Tui {
+    game: Game,
+    /// When the last shot was taken, if the turn is ending.
+    turn_ended: Option<Instant>,
+    /// When the CPU player's turn started, if it's their turn.
+    cpu_turn_started: Option<Instant>,
+    /// The spaces chosen so far in the human player's volley, when they fire more than one shot
+    /// per turn.
+    volley: Vec<[u8; 2]>,
+    message: String,
+    quit: bool,
+}
+
+/// Puts the terminal into raw mode on an alternate screen, and restores it when dropped, even if
+/// the game panics.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new() -> io::Result<TerminalGuard> {
+        terminal::enable_raw_mode()?;
+        execute!(
+            io::stdout(),
+            terminal::EnterAlternateScreen,
+            terminal::Clear(ClearType::All),
+            cursor::Hide
+        )?;
+
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn main() -> io::Result<()> {
+    let settings = Config::load(env::args().skip(1))
+        .and_then(terminal_settings)
+        .unwrap_or_else(|e| {
+            eprintln!("error: {}\n\n{}", e, config::USAGE);
+            process::exit(2);
+        });
+    let game = Game::new(settings).expect("failed to create game");
+    let mut tui = Tui {
+        game,
+        turn_ended: None,
+        cpu_turn_started: None,
+        volley: Vec::new(),
+        message: "Place your ships.".to_string(),
+        quit: false,
+    };
+    let _guard = TerminalGuard::new()?;
+    let mut stdout = io::stdout();
+
+    while !tui.quit {
+        tui.draw(&mut stdout)?;
+
+        if event::poll(Duration::from_millis(50))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    tui.key_press(key.code);
+                }
+            }
+        }
+
+        tui.update(Instant::now());
+    }
+
+    Ok(())
+}
+
+/// Returns the settings for a game in the terminal from `config`, or why its game can't be played
+/// in the terminal.
+fn terminal_settings(config: Config) -> Result<GameSettings, String> {
+    if !matches!(config.launch, Launch::Local) || config.game.mode != GameMode::Cpu {
+        Err("the terminal version only plays against the CPU on this device".to_string())
+    } else if config.game.spaces[0] > MAX_COLUMNS {
+        Err(format!(
+            "the terminal version only plays on grids up to {} columns wide",
+            MAX_COLUMNS
+        ))
+    } else {
+        Ok(config.game)
+    }
+}
+
+impl Tui {
+    /// Processes key presses according to the current program state.
+    fn key_press(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Left => self.movement(Direction::West),
+            KeyCode::Right => self.movement(Direction::East),
+            KeyCode::Up => self.movement(Direction::North),
+            KeyCode::Down => self.movement(Direction::South),
+            KeyCode::Enter => self.button_primary(),
+            KeyCode::Char(' ') => self.button_secondary(),
+            KeyCode::Char('a') => self.auto_place_fleet(),
+            KeyCode::Char('z') => self.undo_placement(),
+            KeyCode::Char('y') => self.redo_placement(),
+            KeyCode::Char(c @ '1'..='3') => self.select_difficulty(c as usize - '1' as usize),
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            _ => {}
+        }
+    }
+
+    /// Performs grid movement according to the current program state.
+    fn movement(&mut self, direction: Direction) {
+        if self.game.is_player_placing_ship() {
+            match self.game.move_ship(direction) {
+                // The ship is already at the edge of the grid, or every ship is placed.
+                Ok(())
+                | Err(BattleshipError::OutOfBounds)
+                | Err(BattleshipError::NoPlacementShip) => {}
+                Err(e) => panic!("failed to move ship: {}", e),
+            }
+        }
+
+        if self.game.is_player_selecting_space() && self.turn_ended.is_none() {
+            // The cursor is already at the edge of the grid.
+            let _ = self.game.move_grid_cursor(direction);
+        }
+    }
+
+    /// Places the placement ship, confirms the fleet once every ship is placed, or chooses the
+    /// space under the grid cursor, firing once the volley has every shot for the turn.
+    fn button_primary(&mut self) {
+        if self.game.is_player_placing_ship() && self.game.active_player_placed_all_ships() {
+            self.game.confirm_fleet().expect("failed to confirm fleet");
+        } else if self.game.is_player_placing_ship() {
+            match self.game.place_ship() {
+                Ok(()) => self.placement_message(),
+                Err(BattleshipError::Overlap) => {
+                    self.message = "A ship can't be placed there.".to_string()
+                }
+                Err(BattleshipError::Adjacent { rule }) => {
+                    self.message = format!("A ship can't be placed there; {}.", rule.description())
+                }
+                Err(e) => panic!("failed to place ship: {}", e),
+            }
+        }
+
+        if self.game.is_player_selecting_space() && self.turn_ended.is_none() {
+            let pos = *self.game.active_player().grid_cursor();
+            let shots = self.game.shots_per_turn();
+
+            // Choosing a chosen space again takes it out of the volley, and choosing a checked
+            // space does nothing; the player can choose another one.
+            if let Some(i) = self.volley.iter().position(|p| *p == pos) {
+                self.volley.remove(i);
+            } else if self.game.target_view().space(&pos).is_unchecked() {
+                self.volley.push(pos);
+            }
+
+            if self.volley.len() == shots {
+                let volley = std::mem::take(&mut self.volley);
+                let outcomes = self
+                    .game
+                    .fire_salvo(&volley)
+                    .expect("failed to fire volley");
+                self.shots_taken(&volley, &outcomes);
+            } else {
+                self.message = format!("{} of {} shots chosen.", self.volley.len(), shots);
+            }
+        }
+    }
+
+    /// Rotates the placement ship.
+    fn button_secondary(&mut self) {
+        if self.game.is_player_placing_ship() {
+            match self.game.rotate_ship() {
+                // The ship is too long to fit on the grid the other way, or every ship is placed.
+                Ok(())
+                | Err(BattleshipError::OutOfBounds)
+                | Err(BattleshipError::NoPlacementShip) => {}
+                Err(e) => panic!("failed to rotate ship: {}", e),
+            }
+        }
+    }
+
+    /// Places the whole fleet randomly.
+    fn auto_place_fleet(&mut self) {
+        if self.game.is_player_placing_ship() {
+            match self.game.auto_place_fleet(AUTO_PLACEMENT) {
+                Ok(()) => self.placement_message(),
+                Err(BattleshipError::FleetDoesNotFit) => {
+                    self.message = "Your fleet couldn't be placed automatically.".to_string()
+                }
+                Err(e) => panic!("failed to place fleet: {}", e),
+            }
+        }
+    }
+
+    /// Undoes the last ship placement, picking that ship up again.
+    fn undo_placement(&mut self) {
+        if self.game.is_player_placing_ship() {
+            match self.game.undo_placement() {
+                Ok(()) => self.placement_message(),
+                Err(BattleshipError::NothingToUndo) => {}
+                Err(e) => panic!("failed to undo placement: {}", e),
+            }
+        }
+    }
+
+    /// Redoes the last undone ship placement.
+    fn redo_placement(&mut self) {
+        if self.game.is_player_placing_ship() {
+            match self.game.redo_placement() {
+                Ok(()) => self.placement_message(),
+                Err(BattleshipError::NothingToRedo) => {}
+                Err(e) => panic!("failed to redo placement: {}", e),
+            }
+        }
+    }
+
+    /// Describes what the player needs to do next to finish placing their ships.
+    fn placement_message(&mut self) {
+        self.message = match self.game.active_player_placed_all_ships() {
+            true => "Press Enter to confirm your fleet, or Z to undo.",
+            false => "Place your ships.",
+        }
+        .to_string();
+    }
+
+    /// Sets the CPU difficulty to the given level, if the game hasn't started yet.
+    fn select_difficulty(&mut self, level: usize) {
+        if self.game.is_state_placement() {
+            self.game
+                .set_difficulty(Difficulty::all()[level])
+                .expect("failed to set difficulty");
+        }
+    }
+
+    /// Describes the outcomes of a turn's shots and starts the end-of-turn delay.
+    fn shots_taken(&mut self, positions: &[[u8; 2]], outcomes: &[ShotOutcome]) {
+        let shooter = match self.game.turn() {
+            HUMAN => "You",
+            _ => "The CPU",
+        };
+        let results = positions
+            .iter()
+            .zip(outcomes)
+            .map(|(pos, outcome)| {
+                let result = match outcome {
+                    ShotOutcome::Miss => "missed".to_string(),
+                    ShotOutcome::Hit { .. } => "hit a ship".to_string(),
+                    ShotOutcome::Sunk { length, .. } => {
+                        format!("sunk a ship of length {}", length)
+                    }
+                    ShotOutcome::Won { length, .. } => {
+                        format!("sunk the last ship, of length {}, and won", length)
+                    }
+                };
+
+                format!("{} and {}", Space::coordinate(pos), result)
+            })
+            .collect::<Vec<_>>();
+
+        self.message = format!("{} fired at {}.", shooter, results.join("; at "));
+        self.turn_ended = Some(Instant::now());
+    }
+
+    fn update(&mut self, now: Instant) {
+        if self.game.is_state_placement() && self.game.active_player_confirmed_fleet() {
+            self.game.switch_active_player();
+
+            if self.game.active_player_confirmed_fleet() {
+                // All ships have been placed; start the game.
+                self.game
+                    .set_state_active()
+                    .expect("failed to start the game");
+                self.message = "Fire at the CPU's grid.".to_string();
+            }
+        } else if let Some(ended) = self.turn_ended {
+            // Continue/end the end-of-turn delay.
+            if self.game.is_state_active() && now - ended >= TURN_END_DELAY {
+                self.game.switch_active_player();
+                self.turn_ended = None;
+            }
+        } else if self.game.is_state_active() && self.game.active_player().is_cpu() {
+            // Continue/end the delay when the CPU player takes their turn.
+            let started = *self.cpu_turn_started.get_or_insert(now);
+
+            if now - started >= CPU_TURN_DELAY {
+                let volley = self.game.suggested_salvo();
+                let outcomes = self
+                    .game
+                    .fire_salvo(&volley)
+                    .expect("CPU player tried to select a checked space");
+                self.cpu_turn_started = None;
+                self.shots_taken(&volley, &outcomes);
+            }
+        }
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let human = &self.game.players()[HUMAN];
+        let target = self.game.target_view_of(1 - HUMAN);
+        let cursor = match self.game.is_player_selecting_space() {
+            true => Some(*self.game.active_player().grid_cursor()),
+            false => None,
+        };
+        let grid_width = self.game.settings().spaces[0] as u16 * CELL_WIDTH + 3;
+
+        queue!(out, cursor::MoveTo(0, 0), Print("Battleship"))?;
+
+        if self.game.is_state_placement() {
+            let difficulty = self.game.settings().difficulty;
+            queue!(
+                out,
+                Print(format!("   CPU difficulty: {:?} (1/2/3)", difficulty))
+            )?;
+        }
+
+        queue!(out, terminal::Clear(ClearType::UntilNewLine))?;
+
+        self.draw_grid(out, "Your fleet", 0, None, |pos| fleet_symbol(human, pos))?;
+        self.draw_grid(out, "Target", grid_width + 4, cursor, |pos| {
+            match self.volley.contains(pos) {
+                true => ("◎", Color::Yellow),
+                false => target_symbol(&target, pos),
+            }
+        })?;
+
+        let status_row = self.game.settings().spaces[1] as u16 + 5;
+        let status = match self.game.get_winner() {
+            Some(HUMAN) => format!("{}  You win! Press q to quit.", self.message),
+            Some(_) => format!("{}  The CPU wins. Press q to quit.", self.message),
+            None => self.message.clone(),
+        };
+        let help = match self.game.is_state_placement() {
+            true => "Arrows: move ship   Space: rotate   Enter: place   a: auto-place   z/y: undo/redo   q: quit",
+            false => "Arrows: move cursor   Enter: fire   q: quit",
+        };
+
+        queue!(
+            out,
+            cursor::MoveTo(0, status_row),
+            Print(status),
+            terminal::Clear(ClearType::UntilNewLine),
+            cursor::MoveTo(0, status_row + 1),
+            Print(help),
+            terminal::Clear(ClearType::UntilNewLine),
+        )?;
+
+        out.flush()
+    }
+
+    /// Draws a grid with its title at column `left`, with each space drawn as `symbol` returns
+    /// and the grid cursor at `cursor`, if it's shown.
+    fn draw_grid(
+        &self,
+        out: &mut impl Write,
+        title: &str,
+        left: u16,
+        cursor: Option<[u8; 2]>,
+        symbol: impl Fn(&[u8; 2]) -> (&'static str, Color),
+    ) -> io::Result<()> {
+        let [columns, rows] = self.game.settings().spaces;
+
+        queue!(out, cursor::MoveTo(left, 2), Print(title))?;
+        queue!(out, cursor::MoveTo(left + 3, 3))?;
+
+        for x in 0..columns {
+            queue!(out, Print(format!("{:<2}", Space::column_name(x))))?;
+        }
+
+        for y in 0..rows {
+            queue!(
+                out,
+                cursor::MoveTo(left, y as u16 + 4),
+                Print(format!("{:>2} ", y + 1))
+            )?;
+
+            for x in 0..columns {
+                let pos = [x, y];
+                let (symbol, color) = symbol(&pos);
+
+                if cursor == Some(pos) {
+                    queue!(out, SetAttribute(Attribute::Reverse))?;
+                }
+
+                queue!(
+                    out,
+                    SetForegroundColor(color),
+                    Print(symbol),
+                    SetAttribute(Attribute::Reset),
+                    ResetColor,
+                    Print(" ")
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the symbol and colour of the space at `pos` on the human player's own grid, which
+/// shows their ships.
+fn fleet_symbol(player: &Player, pos: &[u8; 2]) -> (&'static str, Color) {
+    let space = player.space(pos);
+    let placement = player.placement_ship().ok().map(|s| s.pos());
+    let ship = player
+        .ships()
+        .iter()
+        .find(|s| !s.is_placement() && s.pos().contains(pos));
+
+    if placement.is_some_and(|p| p.contains(pos)) {
+        ("■", Color::Yellow)
+    } else if space.is_hit() && ship.is_some_and(|s| s.is_sunk()) {
+        ("✕", Color::DarkRed)
+    } else if space.is_hit() {
+        ("✕", Color::Red)
+    } else if space.is_empty() {
+        ("○", Color::Grey)
+    } else if space.is_known_empty() {
+        ("-", Color::DarkGrey)
+    } else if ship.is_some() {
+        ("■", Color::White)
+    } else {
+        ("·", Color::Blue)
+    }
+}
+
+/// Returns the symbol and colour of the space at `pos` on the CPU player's grid, as far as the
+/// human player knows.
+fn target_symbol(target: &TargetView, pos: &[u8; 2]) -> (&'static str, Color) {
+    let space = target.space(pos);
+    let sunk = (0..target.ship_lengths().len())
+        .any(|i| target.sunk_ship(i).is_some_and(|ship| ship.contains(pos)));
+
+    if space.is_hit() && sunk {
+        ("✕", Color::DarkRed)
+    } else if space.is_hit() {
+        ("✕", Color::Red)
+    } else if space.is_empty() {
+        ("○", Color::Grey)
+    } else if space.is_known_empty() {
+        ("-", Color::DarkGrey)
+    } else {
+        ("·", Color::Blue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_battleship::ShotRule;
+
+    fn tui() -> Tui {
+        with_settings(GameSettings::defaults())
+    }
+
+    fn with_settings(settings: GameSettings) -> Tui {
+        let settings = GameSettings {
+            seed: Some(1),
+            ..settings
+        };
+
+        Tui {
+            game: Game::new(settings).unwrap(),
+            turn_ended: None,
+            cpu_turn_started: None,
+            volley: Vec::new(),
+            message: String::new(),
+            quit: false,
+        }
+    }
+
+    /// Ends the CPU player's turn if it's their turn, so that it's the human player's turn.
+    fn skip_cpu_turn(tui: &mut Tui, now: Instant) {
+        if tui.game.turn() != HUMAN {
+            tui.update(now);
+            tui.update(now + CPU_TURN_DELAY);
+            tui.update(tui.turn_ended.unwrap() + TURN_END_DELAY);
+        }
+    }
+
+    #[test]
+    fn settings() {
+        let config = |spaces, mode, launch| Config {
+            space_size: 20,
+            game: GameSettings {
+                spaces,
+                mode,
+                ..GameSettings::defaults()
+            },
+            launch,
+        };
+
+        assert!(terminal_settings(config([26, 10], GameMode::Cpu, Launch::Local)).is_ok());
+        assert!(terminal_settings(config([27, 10], GameMode::Cpu, Launch::Local)).is_err());
+        assert!(terminal_settings(config([10, 10], GameMode::Hotseat, Launch::Local)).is_err());
+
+        let host = Launch::Host("0.0.0.0:7878".to_string());
+        let network = GameMode::Network { host: true };
+        assert!(terminal_settings(config([10, 10], network, host)).is_err());
+    }
+
+    #[test]
+    fn salvo() {
+        let mut tui = with_settings(GameSettings {
+            shot_rule: ShotRule::Fixed(2),
+            ..GameSettings::defaults()
+        });
+        place_fleet(&mut tui);
+        let start = Instant::now();
+        tui.update(start);
+        skip_cpu_turn(&mut tui, start);
+        assert_eq!(tui.game.turn(), HUMAN);
+
+        // The second choice of a space takes it out of the volley again.
+        tui.key_press(KeyCode::Enter);
+        assert_eq!(tui.message, "1 of 2 shots chosen.");
+        tui.key_press(KeyCode::Enter);
+        assert_eq!(tui.message, "0 of 2 shots chosen.");
+        tui.key_press(KeyCode::Enter);
+        tui.key_press(KeyCode::Down);
+        assert!(tui.turn_ended.is_none());
+
+        tui.key_press(KeyCode::Enter);
+        assert!(tui.message.starts_with("You fired at A1 and "));
+        assert!(tui.message.contains("; at A2 and "));
+        assert!(tui.volley.is_empty());
+        let target = tui.game.target_view_of(1 - HUMAN);
+        assert_ne!(target_symbol(&target, &[0, 0]).0, "·");
+        assert_ne!(target_symbol(&target, &[0, 1]).0, "·");
+
+        // The CPU player fires a whole volley too.
+        let ended = tui.turn_ended.unwrap() + TURN_END_DELAY;
+        tui.update(ended);
+        tui.update(ended);
+        tui.update(ended + CPU_TURN_DELAY);
+        assert!(tui.message.starts_with("The CPU fired at"));
+        assert!(tui.message.contains("; at "));
+    }
+
+    /// Places each of the human player's ships on every other row, starting from the top, and
+    /// confirms the fleet.
+    fn place_fleet(tui: &mut Tui) {
+        for row in 0..tui.game.settings().ships.len() {
+            for _ in 0..row * 2 {
+                tui.key_press(KeyCode::Down);
+            }
+
+            tui.key_press(KeyCode::Enter);
+        }
+
+        tui.key_press(KeyCode::Enter);
+    }
+
+    #[test]
+    fn placement() {
+        let mut tui = tui();
+
+        tui.key_press(KeyCode::Enter);
+        tui.key_press(KeyCode::Enter);
+        assert_eq!(tui.message, "A ship can't be placed there.");
+        assert_eq!(tui.game.active_player().ships().len(), 2);
+
+        tui.key_press(KeyCode::Down);
+        tui.key_press(KeyCode::Down);
+        tui.key_press(KeyCode::Enter);
+        assert_eq!(tui.message, "Place your ships.");
+        assert_eq!(tui.game.active_player().ships()[1].pos()[0], [0, 2]);
+
+        tui.key_press(KeyCode::Char('z'));
+        assert_eq!(tui.game.active_player().ships().len(), 2);
+        assert_eq!(
+            tui.game.active_player().placement_ship().unwrap().pos()[0],
+            [0, 2]
+        );
+        tui.key_press(KeyCode::Char('y'));
+        assert_eq!(tui.game.active_player().ships().len(), 3);
+
+        let mut tui = self::tui();
+        place_fleet(&mut tui);
+        assert!(tui.game.active_player_confirmed_fleet());
+
+        tui.update(Instant::now());
+        assert!(tui.game.is_state_active());
+        assert_eq!(tui.message, "Fire at the CPU's grid.");
+    }
+
+    #[test]
+    fn turns() {
+        let mut tui = tui();
+        place_fleet(&mut tui);
+        let start = Instant::now();
+        tui.update(start);
+
+        if tui.game.turn() != HUMAN {
+            // The CPU player waits before choosing a space.
+            tui.update(start);
+            assert!(tui.turn_ended.is_none());
+            tui.update(start + CPU_TURN_DELAY);
+            assert!(tui.message.starts_with("The CPU fired at"));
+
+            let ended = tui.turn_ended.unwrap();
+            tui.update(ended + TURN_END_DELAY);
+        }
+
+        assert_eq!(tui.game.turn(), HUMAN);
+        tui.key_press(KeyCode::Right);
+        tui.key_press(KeyCode::Down);
+        tui.key_press(KeyCode::Enter);
+        assert!(tui.message.starts_with("You fired at B2"));
+
+        // The grid cursor doesn't move until the turn has ended.
+        tui.key_press(KeyCode::Right);
+        assert_eq!(tui.game.active_player().grid_cursor(), &[1, 1]);
+        tui.update(tui.turn_ended.unwrap() + TURN_END_DELAY);
+        assert_ne!(tui.game.turn(), HUMAN);
+    }
+
+    #[test]
+    fn target_symbols() {
+        let mut target = TargetView::new([4, 4], vec![2, 3]);
+        target
+            .record_shot(&[0, 0], ShotOutcome::Hit { ship_index: 0 })
+            .unwrap();
+        target.record_shot(&[0, 1], ShotOutcome::Miss).unwrap();
+
+        assert_eq!(target_symbol(&target, &[0, 0]), ("✕", Color::Red));
+        assert_eq!(target_symbol(&target, &[0, 1]), ("○", Color::Grey));
+        assert_eq!(target_symbol(&target, &[3, 3]), ("·", Color::Blue));
+
+        target
+            .record_shot(
+                &[1, 0],
+                ShotOutcome::Sunk {
+                    ship_index: 0,
+                    length: 2,
+                },
+            )
+            .unwrap();
+        assert_eq!(target_symbol(&target, &[0, 0]), ("✕", Color::DarkRed));
+        assert_eq!(target_symbol(&target, &[1, 0]), ("✕", Color::DarkRed));
+    }
+
+    #[test]
+    fn fleet_symbols() {
+        let mut player = Player::new([4, 4], 2, false);
+        player.add_ship([0, 0], Direction::West, 2, false).unwrap();
+        player.add_ship([0, 2], Direction::West, 3, true).unwrap();
+
+        assert_eq!(fleet_symbol(&player, &[1, 0]), ("■", Color::White));
+        assert_eq!(fleet_symbol(&player, &[2, 2]), ("■", Color::Yellow));
+        assert_eq!(fleet_symbol(&player, &[3, 3]), ("·", Color::Blue));
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{error::BattleshipError, ship::Ship};
+use sha2::{Digest, Sha256};
+use std::{fmt, str::FromStr};
+
+/// A hash of a player's fleet layout and a secret salt, published when they finish placing their
+/// ships so that the layout can't be changed without their opponent noticing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FleetCommitment([u8; 32]);
+
+impl fmt::Display for FleetCommitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", to_hex(&self.0))
+    }
+}
+
+impl FromStr for FleetCommitment {
+    type Err = BattleshipError;
+
+    fn from_str(s: &str) -> Result<FleetCommitment, BattleshipError> {
+        from_hex(s).map(FleetCommitment)
+    }
+}
+
+/// A player's fleet layout and the salt used to commit to it, revealed at the end of the game.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FleetReveal {
+    salt: [u8; 16],
+    ships: Vec<Vec<[u8; 2]>>,
+}
+
+impl FleetReveal {
+    /// Creates a reveal for `ships` with a random salt.
+    pub fn new(ships: &[Ship]) -> FleetReveal {
+        FleetReveal::with_salt(
+            rand::random(),
+            ships.iter().map(|s| s.pos().to_vec()).collect(),
+        )
+    }
+
+    /// Creates a reveal for the given salt and ship positions.
+    pub fn with_salt(salt: [u8; 16], ships: Vec<Vec<[u8; 2]>>) -> FleetReveal {
+        FleetReveal { salt, ships }
+    }
+
+    /// Returns the salt.
+    pub fn salt(&self) -> &[u8; 16] {
+        &self.salt
+    }
+
+    /// Returns the positions of each ship, in placement order.
+    pub fn ships(&self) -> &[Vec<[u8; 2]>] {
+        &self.ships
+    }
+
+    /// Returns the commitment to this fleet layout and salt.
+    ///
+    /// The hash covers the salt followed by each ship's length and positions, so that no two
+    /// different layouts have the same input.
+    pub fn commitment(&self) -> FleetCommitment {
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt);
+
+        for ship in &self.ships {
+            hasher.update((ship.len() as u32).to_be_bytes());
+
+            for pos in ship {
+                hasher.update(pos);
+            }
+        }
+
+        FleetCommitment(hasher.finalize().into())
+    }
+}
+
+/// A way in which a remote player's revealed fleet doesn't match what they reported during the
+/// game.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RevealMismatch {
+    /// The revealed fleet and salt don't match the commitment made at the end of placement.
+    Commitment,
+    /// The revealed ships aren't a valid fleet for the game's settings.
+    InvalidFleet,
+    /// The reported outcome of the shot at this position doesn't match the revealed ships.
+    Shot([u8; 2]),
+}
+
+impl fmt::Display for RevealMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevealMismatch::Commitment => {
+                write!(f, "revealed fleet doesn't match the committed fleet")
+            }
+            RevealMismatch::InvalidFleet => write!(f, "revealed fleet isn't a valid fleet"),
+            RevealMismatch::Shot(pos) => write!(
+                f,
+                "reported outcome of the shot at ({}, {}) doesn't match the revealed fleet",
+                pos[0], pos[1]
+            ),
+        }
+    }
+}
+
+/// Returns `bytes` as lowercase hexadecimal.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Parses `N` bytes from hexadecimal.
+///
+/// # Errors
+///
+/// Returns an error if `s` isn't exactly `N` bytes of hexadecimal.
+pub(crate) fn from_hex<const N: usize>(s: &str) -> Result<[u8; N], BattleshipError> {
+    let mut bytes = [0; N];
+
+    // `from_str_radix` would also accept a sign, and slicing by byte needs ASCII.
+    if s.len() != N * 2 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(BattleshipError::InvalidHex);
+    }
+
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16)
+            .map_err(|_| BattleshipError::InvalidHex)?;
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commitment() {
+        let ships = vec![vec![[0, 0], [1, 0]], vec![[0, 2], [0, 3], [0, 4]]];
+        let reveal = FleetReveal::with_salt([7; 16], ships.clone());
+        let commitment = reveal.commitment();
+
+        assert_eq!(commitment.to_string().parse(), Ok(commitment));
+        assert_eq!(
+            FleetReveal::with_salt([7; 16], ships.clone()).commitment(),
+            commitment
+        );
+        assert_ne!(
+            FleetReveal::with_salt([8; 16], ships).commitment(),
+            commitment
+        );
+        assert_ne!(
+            FleetReveal::with_salt(
+                [7; 16],
+                vec![vec![[0, 0], [1, 0], [0, 2]], vec![[0, 3], [0, 4]]]
+            )
+            .commitment(),
+            commitment
+        );
+        assert_eq!(
+            "abc".parse::<FleetCommitment>(),
+            Err(BattleshipError::InvalidHex)
+        );
+        assert_eq!(from_hex::<2>("0aFf"), Ok([0x0a, 0xff]));
+        assert_eq!(from_hex::<2>("+a+b"), Err(BattleshipError::InvalidHex));
+        assert_eq!(from_hex::<2>("0éa"), Err(BattleshipError::InvalidHex));
+        assert_eq!(from_hex::<2>("0x0a"), Err(BattleshipError::InvalidHex));
+    }
+}
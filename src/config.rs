@@ -0,0 +1,362 @@
+use crate::parse::{parse_adjacency, parse_difficulty, parse_fleet, parse_grid, parse_shots};
+use rust_battleship::{AdjacencyRule, Difficulty, GameMode, GameSettings, ShotRule};
+use serde::Deserialize;
+use std::{fs, io, path::PathBuf};
+
+pub const USAGE: &str = "usage: rust-battleship [OPTIONS]
+       rust-battleship --replay FILE
+       battleship-tui [OPTIONS]
+
+Options:
+  --config FILE             read settings from FILE instead of battleship.toml
+  --mode cpu|hotseat|net    play against the CPU, another player on this device, or over TCP
+  --host ADDR               host a network game on ADDR, such as 0.0.0.0:7878
+  --join ADDR               join the network game at ADDR, using the host's grid and fleet
+  --grid COLUMNSxROWS       the size of each grid, such as 12x12
+  --fleet LENGTHS           the length of each ship, such as 5,4,3,3,2
+  --difficulty LEVEL        the CPU difficulty: easy, normal or hard
+  --shots single|salvo|N    how many spaces each player selects per turn
+  --adjacency RULE          whether ships may touch: allowed, no-orthogonal or no-touch
+  --mark-around-sunk BOOL   whether to mark the spaces around sunk ships that can't hold a ship
+  --space-size PIXELS       the size of each grid space, if the window has room
+  --seed N                  the seed for every random choice in the game
+
+Options other than --config and --replay can also be set in the settings file, such as
+`grid = \"12x12\"` or `fleet = [5, 4, 3, 3, 2]`, and options on the command line take
+precedence.  The terminal version only plays against the CPU, on grids up to 26 columns wide,
+and has no use for --space-size.";
+
+/// The settings file that's read, if it exists, when no other file is given.
+const DEFAULT_CONFIG_FILE: &str = "battleship.toml";
+
+/// The smallest grid space size that can be chosen.
+const MIN_SPACE_SIZE: u32 = 4;
+
+/// What the app should do once it starts.
+pub enum Launch {
+    /// Play a game on this device.
+    Local,
+    /// Host a network game on the given address.
+    Host(String),
+    /// Join the network game at the given address.
+    Join(String),
+    /// Watch the replay in the given file.
+    Replay(PathBuf),
+}
+
+/// The app's settings, from the settings file and the command line.
+pub struct Config {
+    /// The size of each grid space in the window.
+    pub space_size: u32,
+    pub game: GameSettings,
+    pub launch: Launch,
+}
+
+/// Settings that were chosen on the command line or in a settings file, where `None` means
+/// the default is used.
+#[derive(Debug, Default, PartialEq)]
+struct Options {
+    config: Option<PathBuf>,
+    replay: Option<PathBuf>,
+    mode: Option<String>,
+    host: Option<String>,
+    join: Option<String>,
+    grid: Option<[u8; 2]>,
+    fleet: Option<Vec<u8>>,
+    difficulty: Option<Difficulty>,
+    shots: Option<ShotRule>,
+    adjacency: Option<AdjacencyRule>,
+    mark_around_sunk: Option<bool>,
+    space_size: Option<u32>,
+    seed: Option<u64>,
+}
+
+/// The settings file's format, which has the same names as the command line options.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct FileOptions {
+    mode: Option<String>,
+    host: Option<String>,
+    join: Option<String>,
+    grid: Option<String>,
+    fleet: Option<Vec<u8>>,
+    difficulty: Option<String>,
+    shots: Option<String>,
+    adjacency: Option<String>,
+    mark_around_sunk: Option<bool>,
+    space_size: Option<u32>,
+    seed: Option<u64>,
+}
+
+impl Config {
+    /// Reads the settings from the command line arguments, and from the settings file named by
+    /// them or the default settings file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem if an argument or the settings file is invalid, or
+    /// if the chosen settings can't be used together.
+    pub fn load(args: impl Iterator<Item = String>) -> Result<Config, String> {
+        let options = Options::from_args(args)?;
+        let path = options
+            .config
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_FILE));
+
+        let file_options = match fs::read_to_string(&path) {
+            Ok(contents) => Options::from_toml(&contents)
+                .map_err(|e| format!("invalid settings file {}: {}", path.display(), e))?,
+            // The default settings file is optional.
+            Err(e) if e.kind() == io::ErrorKind::NotFound && options.config.is_none() => {
+                Options::default()
+            }
+            Err(e) => return Err(format!("failed to read {}: {}", path.display(), e)),
+        };
+
+        options.or(file_options).into_config()
+    }
+}
+
+impl Options {
+    /// Parses command line arguments.
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options::default();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+
+            match arg.as_str() {
+                "--config" => options.config = Some(value()?.into()),
+                "--replay" => options.replay = Some(value()?.into()),
+                "--mode" => options.mode = Some(value()?),
+                "--host" => options.host = Some(value()?),
+                "--join" => options.join = Some(value()?),
+                "--grid" => options.grid = Some(parse_grid(&value()?)?),
+                "--fleet" => options.fleet = Some(parse_fleet(&value()?)?),
+                "--difficulty" => options.difficulty = Some(parse_difficulty(&value()?)?),
+                "--shots" => options.shots = Some(parse_shots(&value()?)?),
+                "--adjacency" => options.adjacency = Some(parse_adjacency(&value()?)?),
+                "--mark-around-sunk" => {
+                    options.mark_around_sunk = Some(
+                        value()?
+                            .parse()
+                            .map_err(|_| "--mark-around-sunk must be true or false".to_string())?,
+                    )
+                }
+                "--space-size" => {
+                    options.space_size = Some(
+                        value()?
+                            .parse()
+                            .map_err(|_| "--space-size must be a number".to_string())?,
+                    )
+                }
+                "--seed" => {
+                    options.seed = Some(
+                        value()?
+                            .parse()
+                            .map_err(|_| "--seed must be a number".to_string())?,
+                    )
+                }
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                other => return Err(format!("unknown argument '{}'", other)),
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Parses the contents of a settings file.
+    fn from_toml(contents: &str) -> Result<Options, String> {
+        let file: FileOptions =
+            toml::from_str(contents).map_err(|e| e.to_string().trim_end().to_string())?;
+
+        Ok(Options {
+            mode: file.mode,
+            host: file.host,
+            join: file.join,
+            grid: file.grid.as_deref().map(parse_grid).transpose()?,
+            fleet: file.fleet,
+            difficulty: file
+                .difficulty
+                .as_deref()
+                .map(parse_difficulty)
+                .transpose()?,
+            shots: file.shots.as_deref().map(parse_shots).transpose()?,
+            adjacency: file.adjacency.as_deref().map(parse_adjacency).transpose()?,
+            mark_around_sunk: file.mark_around_sunk,
+            space_size: file.space_size,
+            seed: file.seed,
+            ..Options::default()
+        })
+    }
+
+    /// Returns these options, with any that weren't chosen taken from `other`.
+    fn or(self, other: Options) -> Options {
+        Options {
+            config: self.config.or(other.config),
+            replay: self.replay.or(other.replay),
+            mode: self.mode.or(other.mode),
+            host: self.host.or(other.host),
+            join: self.join.or(other.join),
+            grid: self.grid.or(other.grid),
+            fleet: self.fleet.or(other.fleet),
+            difficulty: self.difficulty.or(other.difficulty),
+            shots: self.shots.or(other.shots),
+            adjacency: self.adjacency.or(other.adjacency),
+            mark_around_sunk: self.mark_around_sunk.or(other.mark_around_sunk),
+            space_size: self.space_size.or(other.space_size),
+            seed: self.seed.or(other.seed),
+        }
+    }
+
+    /// Checks that the options can be used together, and turns them into the app's settings.
+    fn into_config(self) -> Result<Config, String> {
+        let defaults = GameSettings::defaults();
+        let space_size = self.space_size.unwrap_or(20);
+
+        if space_size < MIN_SPACE_SIZE {
+            return Err(format!("--space-size must be at least {}", MIN_SPACE_SIZE));
+        }
+
+        let launch = match (self.replay, self.host, self.join) {
+            (Some(path), None, None) => Launch::Replay(path),
+            (None, Some(addr), None) => Launch::Host(addr),
+            (None, None, Some(addr)) => Launch::Join(addr),
+            (None, None, None) => Launch::Local,
+            _ => return Err("--host, --join and --replay can't be used together".to_string()),
+        };
+        let mode = match (self.mode.as_deref(), &launch) {
+            (Some("cpu") | None, Launch::Local | Launch::Replay(_)) => GameMode::Cpu,
+            (Some("hotseat"), Launch::Local | Launch::Replay(_)) => GameMode::Hotseat,
+            (Some("net") | None, Launch::Host(_)) => GameMode::Network { host: true },
+            (Some("net") | None, Launch::Join(_)) => GameMode::Network { host: false },
+            (Some("net"), _) => return Err("--mode net needs --host or --join".to_string()),
+            (Some(mode @ ("cpu" | "hotseat")), _) => {
+                return Err(format!(
+                    "--mode {} can't be used with --host or --join",
+                    mode
+                ))
+            }
+            (Some(other), _) => {
+                return Err(format!(
+                    "unknown mode '{}', expected cpu, hotseat or net",
+                    other
+                ))
+            }
+        };
+        let game = GameSettings {
+            spaces: self.grid.unwrap_or(defaults.spaces),
+            ships: self.fleet.unwrap_or(defaults.ships),
+            mode,
+            difficulty: self.difficulty.unwrap_or(defaults.difficulty),
+            shot_rule: self.shots.unwrap_or(defaults.shot_rule),
+            adjacency: self.adjacency.unwrap_or(defaults.adjacency),
+            mark_around_sunk: self.mark_around_sunk.unwrap_or(defaults.mark_around_sunk),
+            seed: self.seed.or(defaults.seed),
+        };
+
+        // The network protocol only has one shot per turn.
+        if matches!(mode, GameMode::Network { .. }) && game.shot_rule != ShotRule::Single {
+            return Err("network games only support single shots".to_string());
+        }
+
+        // Neither instance knows where the other player's ships are, so it can't mark around them.
+        if matches!(mode, GameMode::Network { .. }) && game.mark_around_sunk {
+            return Err("--mark-around-sunk can't be used in network games".to_string());
+        }
+
+        game.validate()
+            .map_err(|e| format!("invalid grid and fleet: {}", e))?;
+
+        Ok(Config {
+            space_size,
+            game,
+            launch,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &str) -> impl Iterator<Item = String> + '_ {
+        args.split_whitespace().map(String::from)
+    }
+
+    #[test]
+    fn from_args() {
+        let options = Options::from_args(args(
+            "--grid 8x12 --fleet 5,4,3,3,2 --difficulty hard --adjacency no-touch --seed 7",
+        ))
+        .unwrap();
+
+        assert_eq!(options.grid, Some([8, 12]));
+        assert_eq!(options.fleet, Some(vec![5, 4, 3, 3, 2]));
+        assert_eq!(options.difficulty, Some(Difficulty::Hard));
+        assert_eq!(
+            options.adjacency,
+            Some(AdjacencyRule::NoTouchIncludingDiagonals)
+        );
+        assert_eq!(options.seed, Some(7));
+
+        assert!(Options::from_args(args("--grid 12")).is_err());
+        assert!(Options::from_args(args("--fleet 5,,3")).is_err());
+        assert!(Options::from_args(args("--difficulty impossible")).is_err());
+        assert!(Options::from_args(args("--shots 0")).is_err());
+        assert!(Options::from_args(args("--adjacency sometimes")).is_err());
+        assert!(Options::from_args(args("--mark-around-sunk maybe")).is_err());
+        assert!(Options::from_args(args("--seed")).is_err());
+        assert!(Options::from_args(args("--colour blue")).is_err());
+    }
+
+    #[test]
+    fn from_toml() {
+        let file = Options::from_toml(
+            "grid = \"12x12\"\nfleet = [5, 4, 3, 3, 2]\nshots = \"salvo\"\nspace-size = 32\n\
+             mark-around-sunk = true\n",
+        )
+        .unwrap();
+
+        assert_eq!(file.grid, Some([12, 12]));
+        assert_eq!(file.shots, Some(ShotRule::Salvo));
+        assert_eq!(file.space_size, Some(32));
+        assert_eq!(file.mark_around_sunk, Some(true));
+        assert!(Options::from_toml("grid = 12").is_err());
+        assert!(Options::from_toml("colour = \"blue\"").is_err());
+
+        // Command line options take precedence over the file.
+        let options = Options::from_args(args("--grid 8x8")).unwrap().or(file);
+        assert_eq!(options.grid, Some([8, 8]));
+        assert_eq!(options.fleet, Some(vec![5, 4, 3, 3, 2]));
+    }
+
+    #[test]
+    fn into_config() {
+        let config = |a| Options::from_args(args(a)).unwrap().into_config();
+
+        let local = config("--mode hotseat --space-size 32").unwrap();
+        assert!(matches!(local.launch, Launch::Local));
+        assert_eq!(local.game.mode, GameMode::Hotseat);
+        assert_eq!(local.space_size, 32);
+
+        let host = config("--host 0.0.0.0:7878").unwrap();
+        assert!(matches!(host.launch, Launch::Host(_)));
+        assert_eq!(host.game.mode, GameMode::Network { host: true });
+
+        assert!(config("--mode net").is_err());
+        assert!(config("--mode cpu --join localhost:7878").is_err());
+        assert!(config("--host 0.0.0.0:7878 --shots salvo").is_err());
+        assert!(config("--host 0.0.0.0:7878 --mark-around-sunk true").is_err());
+        assert!(config("--mark-around-sunk true").is_ok());
+        assert!(config("--host 0.0.0.0:7878 --replay game.json").is_err());
+        assert!(config("--space-size 2").is_err());
+        assert_eq!(
+            config("--grid 4x4 --fleet 4,4,4 --adjacency no-orthogonal").err(),
+            Some("invalid grid and fleet: fleet can not be placed on the grid".to_string())
+        );
+    }
+}
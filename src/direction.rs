@@ -1,14 +1,22 @@
-use rand::{thread_rng, Rng};
+use crate::error::BattleshipError;
+use rand::Rng;
 
+/// A direction on a player's grid, where north is towards the first row.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
+    /// Towards the first row.
     North,
+    /// Towards the last column.
     East,
+    /// Towards the last row.
     South,
+    /// Towards the first column.
     West,
 }
 
 impl Direction {
+    /// Returns the direction opposite to this one.
     pub fn opposite(&self) -> Direction {
         match *self {
             Direction::North => Direction::South,
@@ -18,6 +26,7 @@ impl Direction {
         }
     }
 
+    /// Returns the direction a quarter turn clockwise from this one.
     pub fn rotated(&self) -> Direction {
         match *self {
             Direction::North => Direction::East,
@@ -27,6 +36,7 @@ impl Direction {
         }
     }
 
+    /// Returns all directions, starting from north and going clockwise.
     pub fn all() -> [Direction; 4] {
         [
             Direction::North,
@@ -36,8 +46,9 @@ impl Direction {
         ]
     }
 
-    pub fn random() -> Direction {
-        match thread_rng().gen_range(0..4) {
+    /// Returns a random direction chosen with `rng`.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Direction {
+        match rng.gen_range(0..4) {
             0 => Direction::North,
             1 => Direction::East,
             2 => Direction::South,
@@ -47,9 +58,12 @@ impl Direction {
     }
 
     /// Returns the direction travelled from `pos1` to `pos2` if the positions
-    /// represent travel in exactly north, south, east or west direction, or
-    /// returns an error.
-    pub fn from_positions(pos1: &[u8; 2], pos2: &[u8; 2]) -> Result<Direction, &'static str> {
+    /// represent travel in exactly north, south, east or west direction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the positions don't represent travel in exactly one direction.
+    pub fn from_positions(pos1: &[u8; 2], pos2: &[u8; 2]) -> Result<Direction, BattleshipError> {
         let x_diff = pos1[0] as i16 - pos2[0] as i16;
         let y_diff = pos1[1] as i16 - pos2[1] as i16;
 
@@ -62,7 +76,7 @@ impl Direction {
         } else if x_diff < 0 && y_diff == 0 {
             Ok(Direction::East)
         } else {
-            Err("positions do not represent a supported direction")
+            Err(BattleshipError::InvalidDirection)
         }
     }
 }
@@ -0,0 +1,135 @@
+use crate::{game::GameState, settings::AdjacencyRule, ship::ShipState};
+use std::{error, fmt};
+
+/// An error returned by a fallible operation of the game engine.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BattleshipError {
+    /// A position, or part of a ship, would be outside of the grid.
+    OutOfBounds,
+    /// A ship would overlap with another ship.
+    Overlap,
+    /// A ship would be next to another ship where the game's adjacency rule doesn't allow it.
+    Adjacent {
+        /// The adjacency rule that the ship would break.
+        rule: AdjacencyRule,
+    },
+    /// A space was selected that had already been checked.
+    AlreadyChecked,
+    /// An action was attempted while the game was in the wrong state.
+    WrongState {
+        /// The state the game needed to be in.
+        expected: GameState,
+        /// The state the game was actually in.
+        actual: GameState,
+    },
+    /// A ship's state was changed from a state that doesn't allow it.
+    WrongShipState {
+        /// The state the ship needed to be in.
+        expected: ShipState,
+        /// The state the ship was actually in.
+        actual: ShipState,
+    },
+    /// A player has no ship in the placement state.
+    NoPlacementShip,
+    /// No ship occupies the given position.
+    NoShipAtPosition,
+    /// A ship was added to a player who already has all of their ships.
+    AllShipsAdded,
+    /// A strategy didn't place every ship in the fleet.
+    IncompleteFleet,
+    /// A grid has no columns or no rows.
+    InvalidGridSize,
+    /// A fleet has no ships.
+    EmptyFleet,
+    /// A fleet has a ship of length zero, or one that is longer than the grid.
+    InvalidShipLength {
+        /// The length of the ship.
+        length: u8,
+    },
+    /// A fleet's ships can't all be placed on the grid together under the placement rules.
+    FleetDoesNotFit,
+    /// A ship's position does not form a continuous horizontal or vertical line.
+    InvalidShipShape,
+    /// Two positions do not represent travel in exactly one direction.
+    InvalidDirection,
+    /// An action for a remote player was attempted on a player who isn't remote.
+    NotRemotePlayer,
+    /// An action for a local player was attempted on a remote player.
+    NotLocalPlayer,
+    /// A volley of shots had the wrong number of shots for the game's shot rule.
+    WrongShotCount {
+        /// The number of shots the volley needed.
+        expected: usize,
+        /// The number of shots in the volley.
+        actual: usize,
+    },
+    /// A placement was undone when there was no placement to undo.
+    NothingToUndo,
+    /// A placement was redone when there was no undone placement to redo.
+    NothingToRedo,
+    /// A ship was lifted while another lifted ship hadn't been placed again.
+    ShipAlreadyLifted,
+    /// A fleet was changed or confirmed after it had already been confirmed.
+    FleetConfirmed,
+    /// A value that should be hexadecimal bytes was the wrong length or had other characters.
+    InvalidHex,
+}
+
+impl fmt::Display for BattleshipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BattleshipError::OutOfBounds => write!(f, "position is out of bounds"),
+            BattleshipError::Overlap => write!(f, "ship overlaps with another ship"),
+            BattleshipError::Adjacent { rule } => {
+                write!(
+                    f,
+                    "ship is next to another ship, but {}",
+                    rule.description()
+                )
+            }
+            BattleshipError::AlreadyChecked => write!(f, "space has already been checked"),
+            BattleshipError::WrongState { expected, actual } => write!(
+                f,
+                "game state is {:?}, but needs to be {:?}",
+                actual, expected
+            ),
+            BattleshipError::WrongShipState { expected, actual } => write!(
+                f,
+                "ship state is {:?}, but needs to be {:?}",
+                actual, expected
+            ),
+            BattleshipError::NoPlacementShip => write!(f, "player has no placement ship"),
+            BattleshipError::NoShipAtPosition => write!(f, "no ship at the given position"),
+            BattleshipError::AllShipsAdded => write!(f, "player already has all of their ships"),
+            BattleshipError::IncompleteFleet => write!(f, "not every ship in the fleet was placed"),
+            BattleshipError::InvalidShipShape => {
+                write!(f, "ship position does not form a continuous line")
+            }
+            BattleshipError::InvalidDirection => {
+                write!(f, "positions do not represent a supported direction")
+            }
+            BattleshipError::NotRemotePlayer => write!(f, "player is not a remote player"),
+            BattleshipError::NotLocalPlayer => write!(f, "player is a remote player"),
+            BattleshipError::WrongShotCount { expected, actual } => write!(
+                f,
+                "volley has {} shots, but needs to have {}",
+                actual, expected
+            ),
+            BattleshipError::InvalidGridSize => write!(f, "grid has no columns or no rows"),
+            BattleshipError::EmptyFleet => write!(f, "fleet has no ships"),
+            BattleshipError::InvalidShipLength { length } => {
+                write!(f, "ship length {} is not valid for the grid", length)
+            }
+            BattleshipError::FleetDoesNotFit => write!(f, "fleet can not be placed on the grid"),
+            BattleshipError::NothingToUndo => write!(f, "there is no placement to undo"),
+            BattleshipError::NothingToRedo => write!(f, "there is no placement to redo"),
+            BattleshipError::ShipAlreadyLifted => {
+                write!(f, "another ship has been lifted and not placed again")
+            }
+            BattleshipError::FleetConfirmed => write!(f, "fleet has already been confirmed"),
+            BattleshipError::InvalidHex => write!(f, "value is not valid hexadecimal"),
+        }
+    }
+}
+
+impl error::Error for BattleshipError {}
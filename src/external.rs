@@ -0,0 +1,480 @@
+use crate::{
+    direction::Direction,
+    outcome::ShotOutcome,
+    player::{ship_position, Player},
+    settings::{AdjacencyRule, Difficulty},
+    space::Space,
+    strategy::{DefaultStrategy, Strategy},
+    target::TargetView,
+};
+use rand::RngCore;
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+/// A strategy that asks an external process where to place ships and which spaces to check,
+/// so that bots written in any language can play.
+///
+/// The engine and the bot exchange lines over the bot's stdin and stdout.  Positions are written
+/// as coordinates made of column letters and a row number, such as `B7` for `[1, 6]`, with the
+/// columns after `Z` named `AA`, `AB` and so on, as in [`Space::coordinate`].
+///
+/// - The engine sends `NEWGAME <columns> <rows> <adjacency> <ship lengths...>`, where the
+///   adjacency rule is `ALLOWED`, `NO-ORTHOGONAL` or `NO-TOUCH`, and the bot replies with
+///   `PLACE <length> <position> <H|V>` for each ship, in the same order, where the position is
+///   the ship's left or top end and `H` or `V` is whether it extends right or down.
+/// - For each turn, the engine sends `TURN` and the bot replies with `SHOT <position>`.
+/// - After each shot, the engine sends `RESULT MISS`, `RESULT HIT`, `RESULT SUNK <length>` or
+///   `RESULT WON <length>`.
+/// - When the bot's reply is late or illegal, the engine sends `ERROR <reason>` and makes the
+///   move for it using a [`DefaultStrategy`].  When that move is the bot's fleet, the engine
+///   then sends `PLACED <length> <position> <H|V>` for each ship it placed, in the same form
+///   as `PLACE`.
+/// - The engine sends `QUIT` when the strategy is dropped.
+pub struct ExternalStrategy {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    timeout: Duration,
+    fallback: DefaultStrategy,
+    faults: usize,
+}
+
+impl ExternalStrategy {
+    /// Starts `command` as a bot, which has `timeout` to reply to each request before the move
+    /// is made for it by a [`DefaultStrategy`] of the given `difficulty`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the process couldn't be started.
+    pub fn spawn(
+        mut command: Command,
+        timeout: Duration,
+        difficulty: Difficulty,
+    ) -> io::Result<ExternalStrategy> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("child has no stdin");
+        let stdout = BufReader::new(child.stdout.take().expect("child has no stdout"));
+        let (sender, lines) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in stdout.lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(ExternalStrategy {
+            child,
+            stdin,
+            lines,
+            timeout,
+            fallback: DefaultStrategy::new(difficulty),
+            faults: 0,
+        })
+    }
+
+    /// Returns the number of times the bot's reply was late or illegal, so its move was made for
+    /// it.
+    pub fn faults(&self) -> usize {
+        self.faults
+    }
+
+    /// Sends `line` to the bot.  A bot that has stopped reading is treated as late to reply to
+    /// every later request, so write errors are ignored here.
+    fn send(&mut self, line: &str) {
+        let _ = writeln!(self.stdin, "{}", line).and_then(|_| self.stdin.flush());
+    }
+
+    /// Sends `request` to the bot, and returns its reply if it arrives in time.
+    ///
+    /// Any earlier replies that arrived too late are discarded first.
+    fn request(&mut self, request: &str) -> Result<String, String> {
+        while self.lines.try_recv().is_ok() {}
+
+        self.send(request);
+
+        match self.lines.recv_timeout(self.timeout) {
+            Ok(line) => Ok(line),
+            Err(RecvTimeoutError::Timeout) => Err("timed out".to_string()),
+            Err(RecvTimeoutError::Disconnected) => Err("bot exited".to_string()),
+        }
+    }
+
+    /// Reports a late or illegal reply to the bot.
+    fn fault(&mut self, reason: &str) {
+        self.faults += 1;
+        self.send(&format!("ERROR {}", reason));
+    }
+
+    /// Reads the bot's placement of each ship in `ships`, checking it against a scratch player
+    /// that follows the `adjacency` rule.
+    fn read_fleet(
+        &mut self,
+        grid_size: [u8; 2],
+        ships: &[u8],
+        adjacency: AdjacencyRule,
+    ) -> Result<Vec<([u8; 2], Direction)>, String> {
+        let mut request = format!(
+            "NEWGAME {} {} {}",
+            grid_size[0],
+            grid_size[1],
+            adjacency.keyword()
+        );
+
+        for len in ships {
+            request.push_str(&format!(" {}", len));
+        }
+
+        let mut player = Player::new(grid_size, ships.len(), true);
+        player.set_adjacency_rule(adjacency);
+        let mut fleet = Vec::with_capacity(ships.len());
+
+        for (i, &len) in ships.iter().enumerate() {
+            let line = match i {
+                0 => self.request(&request)?,
+                _ => self
+                    .lines
+                    .recv_timeout(self.timeout)
+                    .map_err(|_| "timed out".to_string())?,
+            };
+            let invalid = || format!("invalid placement '{}'", line);
+
+            let (head, dir) = match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["PLACE", l, pos, dir] if l.parse() == Ok(len) => {
+                    let head = Space::parse_coordinate(pos).ok_or_else(invalid)?;
+                    let dir = match *dir {
+                        "H" => Direction::West,
+                        "V" => Direction::North,
+                        _ => return Err(invalid()),
+                    };
+
+                    (head, dir)
+                }
+                _ => return Err(invalid()),
+            };
+
+            player
+                .add_ship(head, dir, len, false)
+                .map_err(|e| format!("illegal placement '{}': {}", line, e))?;
+            fleet.push((head, dir));
+        }
+
+        Ok(fleet)
+    }
+
+    /// Reads the bot's next shot, checking that it's an unchecked space on `opponent`'s grid.
+    fn read_shot(&mut self, opponent: &TargetView) -> Result<[u8; 2], String> {
+        let line = self.request("TURN")?;
+        let pos = match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["SHOT", pos] => Space::parse_coordinate(pos),
+            _ => None,
+        }
+        .ok_or_else(|| format!("invalid shot '{}'", line))?;
+
+        if opponent.unchecked_spaces().contains(&pos) {
+            Ok(pos)
+        } else {
+            Err(format!("illegal shot '{}'", line))
+        }
+    }
+}
+
+impl Strategy for ExternalStrategy {
+    fn choose_shot(&mut self, opponent: &TargetView, rng: &mut dyn RngCore) -> [u8; 2] {
+        self.read_shot(opponent).unwrap_or_else(|reason| {
+            self.fault(&reason);
+            self.fallback.choose_shot(opponent, rng)
+        })
+    }
+
+    fn place_fleet(
+        &mut self,
+        grid_size: [u8; 2],
+        ships: &[u8],
+        adjacency: AdjacencyRule,
+        rng: &mut dyn RngCore,
+    ) -> Vec<([u8; 2], Direction)> {
+        self.read_fleet(grid_size, ships, adjacency)
+            .unwrap_or_else(|reason| {
+                self.fault(&reason);
+
+                let fleet = self.fallback.place_fleet(grid_size, ships, adjacency, rng);
+
+                // Tell the bot where its ships are, since they aren't where it placed them.
+                for (&(head, dir), &len) in fleet.iter().zip(ships) {
+                    if let Some(pos) = ship_position(grid_size, head, dir, len) {
+                        let start = pos.iter().min().expect("ship has spaces");
+                        let dir = match dir {
+                            Direction::East | Direction::West => "H",
+                            Direction::North | Direction::South => "V",
+                        };
+
+                        self.send(&format!(
+                            "PLACED {} {} {}",
+                            len,
+                            Space::coordinate(start),
+                            dir
+                        ));
+                    }
+                }
+
+                fleet
+            })
+    }
+
+    fn shot_outcome(&mut self, _pos: [u8; 2], outcome: ShotOutcome) {
+        let line = match outcome {
+            ShotOutcome::Miss => "RESULT MISS".to_string(),
+            ShotOutcome::Hit { .. } => "RESULT HIT".to_string(),
+            ShotOutcome::Sunk { length, .. } => format!("RESULT SUNK {}", length),
+            ShotOutcome::Won { length, .. } => format!("RESULT WON {}", length),
+        };
+
+        self.send(&line);
+    }
+}
+
+impl Drop for ExternalStrategy {
+    fn drop(&mut self) {
+        self.send("QUIT");
+
+        // Give the bot a moment to exit by itself before stopping it.
+        for _ in 0..10 {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::{game::Game, settings::GameSettings};
+
+    /// A bot that places its ships in separate rows and checks spaces in order.
+    const ORDERED_BOT: &str = r#"
+        read -r cmd cols rows adjacency ships
+        row=1
+        for len in $ships; do echo "PLACE $len A$row H"; row=$((row + 2)); done
+        n=0
+        while read -r line; do
+            case "$line" in
+                TURN)
+                    col=$(echo ABCDEFGHIJKLMNOPQRSTUVWXYZ | cut -c$((n / rows + 1)))
+                    echo "SHOT $col$((n % rows + 1))"
+                    n=$((n + 1));;
+                QUIT) exit 0;;
+            esac
+        done
+    "#;
+
+    fn bot(script: &str) -> Box<ExternalStrategy> {
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+
+        Box::new(
+            ExternalStrategy::spawn(command, Duration::from_millis(500), Difficulty::Normal)
+                .unwrap(),
+        )
+    }
+
+    /// Plays a game between `strategy` and the built-in strategy, and returns the winner.
+    fn play(strategy: Box<dyn Strategy>) -> usize {
+        let settings = GameSettings {
+            seed: Some(3),
+            ..GameSettings::defaults()
+        };
+        let mut game = Game::with_strategies(
+            settings,
+            [
+                Some(strategy),
+                Some(Box::new(DefaultStrategy::new(Difficulty::Easy))),
+            ],
+        )
+        .unwrap();
+        game.set_state_active().unwrap();
+
+        while !game.is_state_complete() {
+            let pos = game.suggested_check();
+            game.select_space(&pos).unwrap();
+
+            if !game.is_state_complete() {
+                game.switch_active_player();
+            }
+        }
+
+        game.get_winner().unwrap()
+    }
+
+    #[test]
+    fn ordered_bot() {
+        let mut strategy = bot(ORDERED_BOT);
+        let fleet = strategy.place_fleet(
+            [10, 10],
+            &[2, 3],
+            AdjacencyRule::NoTouchIncludingDiagonals,
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(
+            fleet,
+            vec![([0, 0], Direction::West), ([0, 2], Direction::West)]
+        );
+        assert_eq!(strategy.faults(), 0);
+
+        // The bot only learns the outcomes of its own shots, which is enough to finish a game.
+        play(bot(ORDERED_BOT));
+    }
+
+    #[test]
+    fn adjacency_rule() {
+        // Touches orthogonally where the rule allows it, and otherwise only diagonally.
+        let script = r#"
+            read -r cmd cols rows adjacency ships
+            case "$adjacency" in
+                ALLOWED) echo "PLACE 2 A1 H"; echo "PLACE 2 A2 H";;
+                *) echo "PLACE 2 A1 H"; echo "PLACE 2 C2 H";;
+            esac
+            while read -r line; do
+                case "$line" in QUIT) exit 0;; esac
+            done
+        "#;
+        let rules = [
+            (AdjacencyRule::Allowed, 0),
+            (AdjacencyRule::NoOrthogonalTouch, 0),
+            (AdjacencyRule::NoTouchIncludingDiagonals, 1),
+        ];
+
+        for (adjacency, faults) in rules {
+            let mut strategy = bot(script);
+            let fleet = strategy.place_fleet([10, 10], &[2, 2], adjacency, &mut rand::thread_rng());
+            assert_eq!(fleet.len(), 2);
+            assert_eq!(strategy.faults(), faults);
+
+            // A fleet that breaks the rule is placed for the bot, so the game can still start.
+            let settings = GameSettings {
+                ships: vec![2, 2],
+                adjacency,
+                ..GameSettings::defaults()
+            };
+            let game = Game::with_strategies(settings, [None, Some(bot(script))]).unwrap();
+            assert_eq!(game.players()[1].ships().len(), 2);
+        }
+    }
+
+    #[test]
+    fn illegal_moves() {
+        // Overlapping ships and repeated shots are made for the bot instead.
+        let mut strategy = bot(r#"
+            read -r cmd cols rows adjacency ships
+            for len in $ships; do echo "PLACE $len A1 H"; done
+            while read -r line; do
+                case "$line" in
+                    TURN) echo "SHOT A1";;
+                    QUIT) exit 0;;
+                esac
+            done
+        "#);
+        let mut opponent = TargetView::new([3, 3], vec![]);
+        let mut rng = rand::thread_rng();
+
+        assert_eq!(
+            strategy
+                .place_fleet([10, 10], &[2, 3], AdjacencyRule::Allowed, &mut rng)
+                .len(),
+            2
+        );
+        assert_eq!(strategy.faults(), 1);
+
+        assert_eq!(strategy.choose_shot(&opponent, &mut rng), [0, 0]);
+        assert_eq!(strategy.faults(), 1);
+
+        opponent.record_shot(&[0, 0], ShotOutcome::Miss).unwrap();
+        assert_ne!(strategy.choose_shot(&opponent, &mut rng), [0, 0]);
+        assert_eq!(strategy.faults(), 2);
+    }
+
+    #[test]
+    fn fallback_placement() {
+        // Shoots at the first space it's told one of its ships was placed in.
+        let mut strategy = bot(r#"
+            read -r cmd cols rows adjacency ships
+            echo "PLACE 9 A1 H"
+            while read -r line; do
+                set -- $line
+                case "$1" in
+                    PLACED) first=${first:-$3};;
+                    TURN) echo "SHOT $first";;
+                    QUIT) exit 0;;
+                esac
+            done
+        "#);
+        let mut rng = rand::thread_rng();
+        let fleet = strategy.place_fleet([10, 10], &[2, 3], AdjacencyRule::Allowed, &mut rng);
+        assert_eq!(strategy.faults(), 1);
+
+        let (head, dir) = fleet[0];
+        let start = *ship_position([10, 10], head, dir, 2)
+            .unwrap()
+            .iter()
+            .min()
+            .unwrap();
+        let opponent = TargetView::new([10, 10], vec![2, 3]);
+        assert_eq!(strategy.choose_shot(&opponent, &mut rng), start);
+        assert_eq!(strategy.faults(), 1);
+    }
+
+    #[test]
+    fn wide_grid() {
+        let mut strategy = bot(r#"
+            read -r cmd cols rows adjacency ships
+            echo "PLACE 2 AB1 H"
+            while read -r line; do
+                case "$line" in
+                    TURN) echo "SHOT AD2";;
+                    QUIT) exit 0;;
+                esac
+            done
+        "#);
+        let mut rng = rand::thread_rng();
+
+        assert_eq!(
+            strategy.place_fleet([30, 2], &[2], AdjacencyRule::Allowed, &mut rng),
+            vec![([27, 0], Direction::West)]
+        );
+        assert_eq!(
+            strategy.choose_shot(&TargetView::new([30, 2], vec![2]), &mut rng),
+            [29, 1]
+        );
+        assert_eq!(strategy.faults(), 0);
+    }
+
+    #[test]
+    fn timeout() {
+        let mut strategy = bot("sleep 5");
+        let mut rng = rand::thread_rng();
+
+        assert_eq!(
+            strategy
+                .place_fleet([10, 10], &[2, 3], AdjacencyRule::Allowed, &mut rng)
+                .len(),
+            2
+        );
+        assert_eq!(strategy.faults(), 1);
+    }
+}
@@ -1,68 +1,279 @@
+use crate::commitment::{FleetCommitment, FleetReveal, RevealMismatch};
 use crate::direction::Direction;
+use crate::error::BattleshipError;
+use crate::outcome::ShotOutcome;
+use crate::placement::PlacementOptions;
 use crate::player::Player;
-use crate::settings::GameSettings;
-use rand::{seq::SliceRandom, thread_rng, Rng};
+use crate::replay::{Replay, ReplayAction};
+use crate::settings::{Difficulty, GameMode, GameSettings, ShotRule};
+use crate::strategy::{DefaultStrategy, Strategy};
+use crate::target::TargetView;
+use rand::{rngs::StdRng, SeedableRng};
+#[cfg(feature = "serde")]
+use std::io;
 
+/// A game of Battleship between two players.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     settings: GameSettings,
     players: [Player; 2],
+    #[cfg_attr(feature = "serde", serde(skip))]
+    strategies: [Option<Box<dyn Strategy>>; 2],
+    #[cfg_attr(feature = "serde", serde(skip, default = "StdRng::from_entropy"))]
+    rng: StdRng,
+    replay: Replay,
+    #[cfg_attr(feature = "serde", serde(default))]
+    remote_ready: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    remote_commitment: Option<FleetCommitment>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    fleet_reveal: Option<FleetReveal>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    fleet_confirmed: [bool; 2],
     state: GameState,
     turn: u8,
 }
 
 impl Game {
-    pub fn new(settings: GameSettings) -> Result<Game, &'static str> {
+    /// Creates a new game with the given settings, between players determined by the settings'
+    /// game mode.
+    ///
+    /// Human players are given their first ship to place, CPU players' ships are placed
+    /// randomly, and remote players' ships are left for their own instance of the game to place.
+    /// In a network game, the local player is active first so they can place their ships.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the settings' fleet doesn't fit on the grid (see
+    /// [`GameSettings::validate`]), or if the human player's first ship could not be created.
+    pub fn new(settings: GameSettings) -> Result<Game, BattleshipError> {
+        let (is_cpu, remote) = match settings.mode {
+            GameMode::Cpu => ([false, true], None),
+            GameMode::Hotseat => ([false, false], None),
+            GameMode::Network { host } => ([false, false], Some(host as usize)),
+        };
+
+        Game::build(settings, [None, None], is_cpu, remote)
+    }
+
+    /// Creates a new game with the given settings, where each player with a strategy is
+    /// computer-controlled and each player without one is human.
+    ///
+    /// Human players are given their first ship to place, and computer-controlled players' ships
+    /// are placed by their strategies.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the settings' fleet doesn't fit on the grid, if a human player's first
+    /// ship could not be created, or if a strategy didn't place every ship or placed them in
+    /// invalid positions.
+    pub fn with_strategies(
+        settings: GameSettings,
+        strategies: [Option<Box<dyn Strategy>>; 2],
+    ) -> Result<Game, BattleshipError> {
+        let is_cpu = [strategies[0].is_some(), strategies[1].is_some()];
+
+        Game::build(settings, strategies, is_cpu, None)
+    }
+
+    /// Creates a new game with the given settings and player types, where the player at index
+    /// `remote`, if any, is controlled by another instance of the game.
+    ///
+    /// Computer-controlled players without a strategy follow the game's difficulty setting, and
+    /// every random choice is made with an RNG seeded from the settings' seed, if it has one.
+    fn build(
+        settings: GameSettings,
+        mut strategies: [Option<Box<dyn Strategy>>; 2],
+        is_cpu: [bool; 2],
+        remote: Option<usize>,
+    ) -> Result<Game, BattleshipError> {
+        settings.validate()?;
+
+        let mut rng = settings
+            .seed
+            .map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
         let grid_size = [settings.spaces[0], settings.spaces[1]];
+        let mut replay = Replay::new(grid_size, settings.ships.len());
         let mut players = [
-            Player::new(grid_size, settings.ships.len(), false),
-            Player::new(grid_size, settings.ships.len(), true),
+            Player::new(grid_size, settings.ships.len(), is_cpu[0]),
+            Player::new(grid_size, settings.ships.len(), is_cpu[1]),
         ];
 
-        for player in &mut players {
-            if !player.is_cpu() {
-                player.add_ship([0, 0], Direction::West, settings.ships[0], true)?;
+        if let Some(remote) = remote {
+            players[remote] = Player::new_remote(grid_size, settings.ships.len());
+        }
+
+        for (i, (player, strategy)) in players.iter_mut().zip(&mut strategies).enumerate() {
+            player.set_adjacency_rule(settings.adjacency);
+            player.set_mark_around_sunk(settings.mark_around_sunk && remote.is_none());
+
+            if player.is_remote() {
+                continue;
+            } else if let (true, Some(strategy)) = (player.is_cpu(), strategy) {
+                let fleet =
+                    strategy.place_fleet(grid_size, &settings.ships, settings.adjacency, &mut rng);
+
+                if fleet.len() != settings.ships.len() {
+                    return Err(BattleshipError::IncompleteFleet);
+                }
+
+                for ((head, dir), &len) in fleet.into_iter().zip(&settings.ships) {
+                    player.add_ship(head, dir, len, false)?;
+                }
+            } else if player.is_cpu() {
+                let placed =
+                    player.auto_place_fleet(&settings.ships, PlacementOptions::default(), &mut rng);
+
+                // The settings were validated, so a fleet that's too tight to place randomly can
+                // still be placed.
+                if placed == Err(BattleshipError::FleetDoesNotFit) {
+                    player.search_place_fleet(&settings.ships)?;
+                } else {
+                    placed?;
+                }
             } else {
-                let mut rng = thread_rng();
-                let mut i = 0;
-
-                while i < settings.ships.len() {
-                    let pos = [
-                        rng.gen_range(0..grid_size[0]),
-                        rng.gen_range(0..grid_size[1]),
-                    ];
-
-                    if player
-                        .add_ship(pos, Direction::random(), settings.ships[i], false)
-                        .is_ok()
-                    {
-                        i += 1;
-                    }
+                add_placement_ship(player, settings.ships[0])?;
+            }
+
+            if player.is_cpu() {
+                for ship in player.ships() {
+                    replay.record(i, ReplayAction::Placement(ship.pos().to_vec()));
                 }
             }
         }
 
         Ok(Game {
-            settings: settings,
-            players: players,
+            settings,
+            players,
+            strategies,
+            rng,
+            replay,
+            remote_ready: false,
+            remote_commitment: None,
+            fleet_reveal: None,
+            fleet_confirmed: is_cpu,
             state: GameState::Placement,
-            turn: 0,
+            turn: (remote == Some(0)) as u8,
         })
     }
 
+    /// Writes the game to `writer` as JSON, so that it can be resumed with [`Game::load`].
+    ///
+    /// Strategies given to [`Game::with_strategies`] and the state of the game's RNG are not
+    /// saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game could not be written.
+    #[cfg(feature = "serde")]
+    pub fn save<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Reads a game from JSON written by [`Game::save`].
+    ///
+    /// Computer-controlled players of the loaded game follow the game's difficulty setting, and
+    /// its RNG is seeded randomly.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game could not be read.
+    #[cfg(feature = "serde")]
+    pub fn load<R: io::Read>(reader: R) -> Result<Game, serde_json::Error> {
+        serde_json::from_reader(reader)
+    }
+
+    /// Returns the record of every ship placement and shot so far.
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Returns a reference to the game's settings.
     pub fn settings(&self) -> &GameSettings {
         &self.settings
     }
 
+    /// Sets the difficulty of CPU players.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game's state is not `GameState::Placement`.
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) -> Result<(), BattleshipError> {
+        self.expect_state(GameState::Placement)?;
+        self.settings.difficulty = difficulty;
+
+        Ok(())
+    }
+
+    /// Returns references to both players, in turn order.
+    pub fn players(&self) -> &[Player; 2] {
+        &self.players
+    }
+
+    /// Returns whether each of the ships in the fleet of the player at index `player` has been
+    /// sunk, in placement order.
+    ///
+    /// This comes from the outcomes of the shots at the player, so it's also known for remote
+    /// players, whose ships aren't.
+    pub fn sunk_ships(&self, player: usize) -> Vec<bool> {
+        let mut sunk = vec![false; self.settings.ships.len()];
+
+        for event in self.replay.events().iter().filter(|e| e.player != player) {
+            if let ReplayAction::Shot {
+                outcome: ShotOutcome::Sunk { ship_index, .. } | ShotOutcome::Won { ship_index, .. },
+                ..
+            } = event.action
+            {
+                // A remote player's reports can't be trusted to refer to a ship in the fleet.
+                if let Some(sunk) = sunk.get_mut(ship_index) {
+                    *sunk = true;
+                }
+            }
+        }
+
+        sunk
+    }
+
     /// Returns a reference to the currently active player.
     pub fn active_player(&self) -> &Player {
         &self.players[self.turn as usize]
     }
 
     /// Returns a reference to the currently inactive player.
+    ///
+    /// This includes the positions of their ships, so [`Game::target_view`] should be used to
+    /// choose the active player's shots.
     pub fn inactive_player(&self) -> &Player {
         &self.players[self.not_turn()]
     }
 
+    /// Returns what the active player knows about the inactive player's grid: the spaces they've
+    /// checked, and the ships they've hit and sunk, without the positions of any other ships.
+    ///
+    /// Which ship each hit space belongs to comes from the outcomes of the active player's shots,
+    /// so it's also known for remote players, whose ships aren't.
+    pub fn target_view(&self) -> TargetView {
+        self.target_view_of(self.not_turn())
+    }
+
+    /// Returns what the opponent of the player at index `player` knows about their grid, in the
+    /// same way as [`Game::target_view`].
+    pub fn target_view_of(&self, player: usize) -> TargetView {
+        let mut view = TargetView::from_spaces(
+            self.settings.spaces,
+            self.players[player].spaces().to_vec(),
+            self.settings.ships.clone(),
+        );
+
+        for event in self.replay.events().iter().filter(|e| e.player != player) {
+            if let ReplayAction::Shot { pos, outcome } = event.action {
+                view.record_outcome(&pos, outcome);
+            }
+        }
+
+        view
+    }
+
     /// Returns whether the game's current state is ship placement.
     pub fn is_state_placement(&self) -> bool {
         self.state == GameState::Placement
@@ -73,18 +284,40 @@ impl Game {
         self.state == GameState::Active
     }
 
-    /// Sets the game state as active. starting the game and setting player 1 as the active player.
+    /// Sets the game state as active, starting the game and setting player 1 as the active player.
+    ///
+    /// Any local human player's fleet that hasn't been confirmed is confirmed.
     ///
     /// # Errors
     ///
     /// Returns an error if the game state was not `GameState::Placement`.
-    pub fn set_state_active(&mut self) -> Result<(), &'static str> {
-        if self.state != GameState::Placement {
-            Err("tried to set game as active from a state other than placement")
-        } else {
-            self.state = GameState::Active;
-            self.turn = 0;
+    pub fn set_state_active(&mut self) -> Result<(), BattleshipError> {
+        self.expect_state(GameState::Placement)?;
 
+        for player in 0..2 {
+            if !self.fleet_confirmed[player] && !self.players[player].is_remote() {
+                self.record_fleet(player);
+            }
+        }
+
+        self.state = GameState::Active;
+        self.turn = 0;
+
+        Ok(())
+    }
+
+    /// Checks that the game's current state is `state`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game's current state is not `state`.
+    fn expect_state(&self, state: GameState) -> Result<(), BattleshipError> {
+        if self.state != state {
+            Err(BattleshipError::WrongState {
+                expected: state,
+                actual: self.state,
+            })
+        } else {
             Ok(())
         }
     }
@@ -109,21 +342,100 @@ impl Game {
         self.turn = self.not_turn() as u8;
     }
 
+    /// Returns whether both players are human and playing on this instance of the game.
+    pub fn is_hotseat(&self) -> bool {
+        self.players.iter().all(|p| !p.is_cpu() && !p.is_remote())
+    }
+
+    /// Returns whether one of the players is controlled by another instance of the game.
+    pub fn is_network(&self) -> bool {
+        self.players.iter().any(|p| p.is_remote())
+    }
+
     /// Returns whether the active player has placed all their ships.
+    ///
+    /// For a remote player, this is whether their instance has reported that they're ready.
     pub fn active_player_placed_all_ships(&self) -> bool {
+        if self.active_player().is_remote() {
+            return self.remote_ready;
+        }
+
         let ships = self.active_player().ships();
 
-        ships.len() == self.settings.ships.len() && !ships[ships.len() - 1].is_placement()
+        ships.len() == self.settings.ships.len() && ships.iter().all(|s| !s.is_placement())
+    }
+
+    /// Returns whether the active player's fleet is final.
+    ///
+    /// A human player's fleet is final once they've confirmed it, a CPU player's is final from the
+    /// start, and a remote player's is final once their instance has reported that they're ready.
+    pub fn active_player_confirmed_fleet(&self) -> bool {
+        if self.active_player().is_remote() {
+            self.remote_ready
+        } else {
+            self.fleet_confirmed[self.turn as usize]
+        }
+    }
+
+    /// Confirms the active player's fleet, after which their ships can't be lifted and their
+    /// placements can't be undone.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game's state is not `GameState::Placement`, if the active player's
+    /// fleet was already confirmed, or if they haven't placed all of their ships.
+    pub fn confirm_fleet(&mut self) -> Result<(), BattleshipError> {
+        self.expect_unconfirmed_fleet()?;
+
+        if self.active_player().is_remote() || !self.active_player_placed_all_ships() {
+            return Err(BattleshipError::IncompleteFleet);
+        }
+
+        self.record_fleet(self.turn as usize);
+
+        Ok(())
     }
 
-    /// Returns whether a human player is currently placing ships.
+    /// Records the placement of each of `player`'s ships in the replay, and marks their fleet as
+    /// confirmed.
+    fn record_fleet(&mut self, player: usize) {
+        for ship in self.players[player].ships() {
+            self.replay
+                .record(player, ReplayAction::Placement(ship.pos().to_vec()));
+        }
+
+        self.fleet_confirmed[player] = true;
+    }
+
+    /// Checks that the game's state is ship placement and that the active player's fleet hasn't
+    /// been confirmed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game's state is not `GameState::Placement`, or if the active
+    /// player's fleet has been confirmed.
+    fn expect_unconfirmed_fleet(&self) -> Result<(), BattleshipError> {
+        self.expect_state(GameState::Placement)?;
+
+        if self.fleet_confirmed[self.turn as usize] {
+            Err(BattleshipError::FleetConfirmed)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns whether a human player on this instance is currently placing ships.
     pub fn is_player_placing_ship(&self) -> bool {
-        self.state == GameState::Placement && !self.active_player().is_cpu()
+        let player = self.active_player();
+
+        self.state == GameState::Placement && !player.is_cpu() && !player.is_remote()
     }
 
-    /// Returns whether a human player is currently selecting a space.
+    /// Returns whether a human player on this instance is currently selecting a space.
     pub fn is_player_selecting_space(&self) -> bool {
-        self.state == GameState::Active && !self.active_player().is_cpu()
+        let player = self.active_player();
+
+        self.state == GameState::Active && !player.is_cpu() && !player.is_remote()
     }
 
     /// Returns as `usize` the winner, if there is one.
@@ -138,28 +450,108 @@ impl Game {
     ///
     /// # Errors
     ///
-    /// Returns an error if the game's state is not `GameState::Placement` or if the active
-    /// player's placement ship overlaps with another ship.
-    pub fn place_ship(&mut self) -> Result<(), &'static str> {
-        if self.state != GameState::Placement {
-            Err("tried to place ship outside of placement game state")
-        } else {
-            let ref mut player = self.players[self.turn as usize];
-            let ship_count = player.ships().len();
-
-            player.place_placement_ship()?;
-
-            // If the player hasn't placed all their ships, add a new one.
-            if ship_count < self.settings.ships.len() {
-                player.add_ship(
-                    [0, 0],
-                    Direction::West,
-                    self.settings.ships[ship_count],
-                    true,
-                )?;
+    /// Returns an error if the game's state is not `GameState::Placement`, if the active
+    /// player's placement ship overlaps with another ship, or if their next ship doesn't fit on
+    /// the grid, in which case the player is left unchanged.
+    pub fn place_ship(&mut self) -> Result<(), BattleshipError> {
+        self.expect_state(GameState::Placement)?;
+
+        let player = &mut self.players[self.turn as usize];
+        let previous = player.clone();
+        player.place_placement_ship()?;
+
+        // If the player hasn't placed all their ships, add a new one.
+        let ship_count = player.ships().len();
+
+        if ship_count < self.settings.ships.len() {
+            if let Err(err) = add_placement_ship(player, self.settings.ships[ship_count]) {
+                *player = previous;
+
+                return Err(err);
             }
+        }
 
-            Ok(())
+        self.update_fleet_reveal();
+
+        Ok(())
+    }
+
+    /// Lifts the active player's placed ship at `pos`, so that it can be moved and placed again.
+    /// If the active player had a placement ship that they hadn't placed yet, it's removed until
+    /// the lifted ship is placed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game's state is not `GameState::Placement`, if the active player's
+    /// fleet has been confirmed, if no placed ship is at `pos`, or if another lifted ship hasn't
+    /// been placed again.
+    pub fn lift_ship(&mut self, pos: &[u8; 2]) -> Result<(), BattleshipError> {
+        self.expect_unconfirmed_fleet()?;
+        self.players[self.turn as usize].lift_ship(pos)?;
+        self.update_fleet_reveal();
+
+        Ok(())
+    }
+
+    /// Undoes the active player's last ship placement or lift.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game's state is not `GameState::Placement`, if the active player's
+    /// fleet has been confirmed, or if there is nothing to undo.
+    pub fn undo_placement(&mut self) -> Result<(), BattleshipError> {
+        self.expect_unconfirmed_fleet()?;
+        self.players[self.turn as usize].undo_placement()?;
+        self.update_fleet_reveal();
+
+        Ok(())
+    }
+
+    /// Redoes the active player's last undone ship placement or lift.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game's state is not `GameState::Placement`, if the active player's
+    /// fleet has been confirmed, or if there is nothing to redo.
+    pub fn redo_placement(&mut self) -> Result<(), BattleshipError> {
+        self.expect_unconfirmed_fleet()?;
+        self.players[self.turn as usize].redo_placement()?;
+        self.update_fleet_reveal();
+
+        Ok(())
+    }
+
+    /// Replaces the active player's ships with a randomly placed fleet, placed according to
+    /// `options`.  This can be undone like any other placement.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game's state is not `GameState::Placement`, if the active player's
+    /// fleet has been confirmed, if the active player is remote, or if a fleet couldn't be placed
+    /// with the given options.
+    pub fn auto_place_fleet(&mut self, options: PlacementOptions) -> Result<(), BattleshipError> {
+        self.expect_unconfirmed_fleet()?;
+
+        let player = &mut self.players[self.turn as usize];
+
+        if player.is_remote() {
+            return Err(BattleshipError::NotLocalPlayer);
+        }
+
+        player.auto_place_fleet(&self.settings.ships, options, &mut self.rng)?;
+        self.update_fleet_reveal();
+
+        Ok(())
+    }
+
+    /// In a network game, commits to the active player's fleet once all of their ships are
+    /// placed, so the remote player can check it at the end, and withdraws the commitment while
+    /// any ship isn't placed.
+    fn update_fleet_reveal(&mut self) {
+        if self.is_network() {
+            self.fleet_reveal = self
+                .active_player_placed_all_ships()
+                .then(|| FleetReveal::new(self.active_player().ships()));
         }
     }
 
@@ -168,14 +560,12 @@ impl Game {
     /// # Errors
     ///
     /// Returns an error if the game's state is not `GameState::Placement`.
-    pub fn move_ship(&mut self, direction: Direction) -> Result<(), &'static str> {
-        if self.state != GameState::Placement {
-            Err("tried to move ship outside of placement game state")
-        } else {
-            self.players[self.turn as usize].move_placement_ship(direction)?;
+    pub fn move_ship(&mut self, direction: Direction) -> Result<(), BattleshipError> {
+        self.expect_state(GameState::Placement)?;
 
-            Ok(())
-        }
+        self.players[self.turn as usize].move_placement_ship(direction)?;
+
+        Ok(())
     }
 
     /// Rotates the active player's placement ship in the given direction.
@@ -183,14 +573,12 @@ impl Game {
     /// # Errors
     ///
     /// Returns an error if the game's state is not `GameState::Placement`.
-    pub fn rotate_ship(&mut self) -> Result<(), &'static str> {
-        if self.state != GameState::Placement {
-            Err("tried to rotate ship outside of placement game state")
-        } else {
-            self.players[self.turn as usize].rotate_placement_ship()?;
+    pub fn rotate_ship(&mut self) -> Result<(), BattleshipError> {
+        self.expect_state(GameState::Placement)?;
 
-            Ok(())
-        }
+        self.players[self.turn as usize].rotate_placement_ship()?;
+
+        Ok(())
     }
 
     /// Sets the active player's placement ship to the given position.
@@ -199,51 +587,306 @@ impl Game {
     ///
     /// Returns an error if the game's state is not `GameState::Placement`, if the active player
     /// has no ships, or if the active player has no placement ship.
-    pub fn set_placement_ship(&mut self, pos: Vec<[u8; 2]>) -> Result<(), &'static str> {
-        if self.state != GameState::Placement {
-            Err("tried to set position of ship outside of placement game state")
-        } else {
-            let ship = self.players[self.turn as usize].placement_ship_mut()?;
-            ship.set_pos(pos)?;
+    pub fn set_placement_ship(&mut self, pos: Vec<[u8; 2]>) -> Result<(), BattleshipError> {
+        self.expect_state(GameState::Placement)?;
 
-            Ok(())
+        let ship = self.players[self.turn as usize].placement_ship_mut()?;
+        ship.set_pos(pos)?;
+
+        Ok(())
+    }
+
+    /// Selects a space on the inactive player's grid if it's unchecked, and returns the outcome
+    /// of the shot, which is also passed to the active player's strategy, if they have one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game's state is not `GameState::Active`, if no space exists at
+    /// `pos`, or if the inactive player's space at `pos` was already checked.
+    pub fn select_space(&mut self, pos: &[u8; 2]) -> Result<ShotOutcome, BattleshipError> {
+        self.expect_state(GameState::Active)?;
+
+        let outcome = shoot(&mut self.players[self.not_turn()], pos)?;
+
+        if let ShotOutcome::Won { .. } = outcome {
+            self.state = GameState::Complete;
+        }
+
+        if let Some(strategy) = &mut self.strategies[self.turn as usize] {
+            strategy.shot_outcome(*pos, outcome);
         }
+
+        self.replay.record(
+            self.turn as usize,
+            ReplayAction::Shot { pos: *pos, outcome },
+        );
+
+        Ok(outcome)
     }
 
-    /// Selects a space on the inactive player's grid if it's unchecked.
+    /// Returns the number of spaces the active player selects this turn under the game's shot
+    /// rule, which is never more than the number of unchecked spaces on the inactive player's
+    /// grid.
+    pub fn shots_per_turn(&self) -> usize {
+        let shots = match self.settings.shot_rule {
+            ShotRule::Single => 1,
+            ShotRule::Salvo => self
+                .active_player()
+                .ships()
+                .iter()
+                .filter(|s| !s.is_sunk())
+                .count(),
+            ShotRule::Fixed(shots) => shots as usize,
+        };
+        let unchecked = self
+            .inactive_player()
+            .spaces()
+            .iter()
+            .filter(|s| s.is_unchecked())
+            .count();
+
+        shots.clamp(1, unchecked.max(1))
+    }
+
+    /// Selects every space in `positions` on the inactive player's grid at once, and returns the
+    /// outcome of each shot in the same order.
+    ///
+    /// If a shot wins the game, the rest of the volley isn't fired.
     ///
     /// # Errors
     ///
-    /// Returns an error if the inactive player's space at `pos` was already
-    /// checked.
-    pub fn select_space(&mut self, pos: &[u8; 2]) -> Result<(), &'static str> {
-        let ref mut opponent = self.players[self.not_turn()];
+    /// Returns an error if the game's state is not `GameState::Active`, if the volley doesn't
+    /// have [`Game::shots_per_turn`] shots, or if any of the positions is out of bounds, already
+    /// checked or repeated.  No shots are fired if there's an error.
+    pub fn fire_salvo(
+        &mut self,
+        positions: &[[u8; 2]],
+    ) -> Result<Vec<ShotOutcome>, BattleshipError> {
+        self.expect_state(GameState::Active)?;
 
-        opponent.select_space(pos)?;
+        let expected = self.shots_per_turn();
 
-        // If it's an error, no ship was at the position; and if it's false, the
-        // ship wasn't sunk
-        if opponent.sink_ship_if_all_hit(pos) == Ok(true) {
-            self.state = match opponent.all_ships_sunk() {
-                true => GameState::Complete,
-                false => GameState::Active,
-            };
+        if positions.len() != expected {
+            return Err(BattleshipError::WrongShotCount {
+                expected,
+                actual: positions.len(),
+            });
+        }
+
+        let grid_size = self.settings.spaces;
+
+        for (i, pos) in positions.iter().enumerate() {
+            if pos[0] >= grid_size[0] || pos[1] >= grid_size[1] {
+                return Err(BattleshipError::OutOfBounds);
+            } else if !self.inactive_player().space(pos).is_unchecked()
+                || positions[..i].contains(pos)
+            {
+                return Err(BattleshipError::AlreadyChecked);
+            }
+        }
+
+        let mut outcomes = Vec::with_capacity(positions.len());
+
+        for pos in positions {
+            outcomes.push(self.select_space(pos)?);
+
+            if self.is_state_complete() {
+                break;
+            }
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Returns the commitment to the local player's fleet in a network game, once they have
+    /// placed all of their ships.
+    pub fn fleet_commitment(&self) -> Option<FleetCommitment> {
+        self.fleet_reveal.as_ref().map(FleetReveal::commitment)
+    }
+
+    /// Returns the local player's fleet and the salt used to commit to it in a network game,
+    /// once they have placed all of their ships.
+    ///
+    /// This should only be sent to the remote player once the game is complete.
+    pub fn fleet_reveal(&self) -> Option<&FleetReveal> {
+        self.fleet_reveal.as_ref()
+    }
+
+    /// Records that the remote player has placed all of their ships, and their commitment to
+    /// their fleet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game's state is not `GameState::Placement`, or if neither player
+    /// is remote.
+    pub fn set_remote_ready(&mut self, commitment: FleetCommitment) -> Result<(), BattleshipError> {
+        self.expect_state(GameState::Placement)?;
+
+        if !self.is_network() {
+            return Err(BattleshipError::NotRemotePlayer);
         }
 
+        self.remote_ready = true;
+        self.remote_commitment = Some(commitment);
+
         Ok(())
     }
 
+    /// Records the outcome of the active player's shot at `pos` on a remote inactive player's
+    /// grid, as reported by the remote player's instance of the game.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game's state is not `GameState::Active`, if the inactive player
+    /// isn't remote, if no space exists at `pos`, or if the space at `pos` was already checked.
+    pub fn record_remote_outcome(
+        &mut self,
+        pos: &[u8; 2],
+        outcome: ShotOutcome,
+    ) -> Result<(), BattleshipError> {
+        self.expect_state(GameState::Active)?;
+
+        let opponent = &mut self.players[self.not_turn()];
+
+        if !opponent.is_remote() {
+            return Err(BattleshipError::NotRemotePlayer);
+        }
+
+        opponent.set_space_checked(pos, outcome.is_hit())?;
+
+        if let ShotOutcome::Won { .. } = outcome {
+            self.state = GameState::Complete;
+        }
+
+        self.replay.record(
+            self.turn as usize,
+            ReplayAction::Shot { pos: *pos, outcome },
+        );
+
+        Ok(())
+    }
+
+    /// Checks the remote player's revealed fleet against their commitment, and checks the
+    /// outcome they reported for each of the local player's shots against the revealed ships.
+    ///
+    /// Returns every mismatch that was found, so an empty list means the remote player played
+    /// fairly.  If the revealed fleet isn't valid, the shots aren't checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game's state is not `GameState::Complete`, or if neither player is
+    /// remote.
+    pub fn verify_remote_fleet(
+        &self,
+        reveal: &FleetReveal,
+    ) -> Result<Vec<RevealMismatch>, BattleshipError> {
+        self.expect_state(GameState::Complete)?;
+
+        let remote = self
+            .players
+            .iter()
+            .position(|p| p.is_remote())
+            .ok_or(BattleshipError::NotRemotePlayer)?;
+        let mut mismatches = vec![];
+
+        if self.remote_commitment != Some(reveal.commitment()) {
+            mismatches.push(RevealMismatch::Commitment);
+        }
+
+        let grid_size = self.settings.spaces;
+        let mut fleet = Player::new(grid_size, self.settings.ships.len(), false);
+        fleet.set_adjacency_rule(self.settings.adjacency);
+        let valid = reveal.ships().len() == self.settings.ships.len()
+            && reveal
+                .ships()
+                .iter()
+                .zip(&self.settings.ships)
+                .all(|(pos, &len)| {
+                    let dir = match pos.len() {
+                        0 => return false,
+                        1 => Direction::West,
+                        _ => match Direction::from_positions(&pos[1], &pos[0]) {
+                            Ok(dir) => dir,
+                            Err(_) => return false,
+                        },
+                    };
+
+                    pos.len() == len as usize
+                        && fleet.add_ship(pos[0], dir, len, false).is_ok()
+                        && fleet.ships().last().unwrap().pos() == pos.as_slice()
+                });
+
+        if !valid {
+            mismatches.push(RevealMismatch::InvalidFleet);
+            return Ok(mismatches);
+        }
+
+        for event in self.replay.events().iter().filter(|e| e.player != remote) {
+            if let ReplayAction::Shot { pos, outcome } = event.action {
+                if shoot(&mut fleet, &pos) != Ok(outcome) {
+                    mismatches.push(RevealMismatch::Shot(pos));
+                }
+            }
+        }
+
+        Ok(mismatches)
+    }
+
     /// Returns an unchecked position on the inactive player's grid as a check suggestion.
     ///
     /// This is intended for use in cases where the active player is computer-controlled, to
     /// determine the space they check.  However, it could also be used to suggest a space that a
     /// human player could check.
-    pub fn suggested_check(&self) -> [u8; 2] {
-        let mut rng = thread_rng();
-        let mut positions = self.inactive_player().suggested_checks();
-        positions.shuffle(&mut rng);
+    ///
+    /// The space is chosen by the active player's strategy, or if they don't have one or their
+    /// strategy chose a space that can't be checked, by the built-in strategy for the game's
+    /// difficulty setting.
+    pub fn suggested_check(&mut self) -> [u8; 2] {
+        let opponent = self.target_view();
+
+        self.choose_shot(&opponent)
+    }
 
-        positions[0]
+    /// Returns [`Game::shots_per_turn`] distinct unchecked positions on the inactive player's
+    /// grid as a volley suggestion, chosen in the same way as [`Game::suggested_check`].
+    ///
+    /// While the volley is being chosen, the positions already in it are treated as misses, since
+    /// their outcomes aren't known until the volley is fired.
+    pub fn suggested_salvo(&mut self) -> Vec<[u8; 2]> {
+        let shots = self.shots_per_turn();
+        let mut opponent = self.target_view();
+        let mut positions = Vec::with_capacity(shots);
+
+        for _ in 0..shots {
+            let pos = self.choose_shot(&opponent);
+
+            opponent
+                .record_shot(&pos, ShotOutcome::Miss)
+                .expect("chose a checked space");
+            positions.push(pos);
+        }
+
+        positions
+    }
+
+    /// Returns the space on `opponent`'s grid that the active player's strategy chooses to check,
+    /// or the space chosen by the built-in strategy if they don't have one or if their strategy's
+    /// choice isn't an unchecked space.
+    fn choose_shot(&mut self, opponent: &TargetView) -> [u8; 2] {
+        let rng = &mut self.rng;
+        let mut fallback = DefaultStrategy::new(self.settings.difficulty);
+
+        match &mut self.strategies[self.turn as usize] {
+            Some(strategy) => {
+                let pos = strategy.choose_shot(opponent, rng);
+
+                match opponent.unchecked_spaces().contains(&pos) {
+                    true => pos,
+                    false => fallback.choose_shot(opponent, rng),
+                }
+            }
+            None => fallback.choose_shot(opponent, rng),
+        }
     }
 
     /// Moves the active player's grid cursor in the given `direction`.
@@ -251,7 +894,7 @@ impl Game {
     /// # Errors
     ///
     /// Returns an error if moving the grid cursor in `direction` would move it out of bounds.
-    pub fn move_grid_cursor(&mut self, direction: Direction) -> Result<(), &'static str> {
+    pub fn move_grid_cursor(&mut self, direction: Direction) -> Result<(), BattleshipError> {
         self.players[self.turn as usize].move_grid_cursor(direction)
     }
 
@@ -260,14 +903,774 @@ impl Game {
     /// # Errors
     ///
     /// Returns an error if no space exists at `pos`.
-    pub fn set_grid_cursor(&mut self, pos: &[u8; 2]) -> Result<(), &'static str> {
+    pub fn set_grid_cursor(&mut self, pos: &[u8; 2]) -> Result<(), BattleshipError> {
         self.players[self.turn as usize].set_grid_cursor(pos)
     }
 }
 
-#[derive(Clone, Copy, PartialEq)]
+/// Gives `player` a placement ship of the given length in the top left corner, lying across the
+/// grid unless it's too long to fit that way.
+///
+/// # Errors
+///
+/// Returns an error if the player already has all of their ships, or if the ship doesn't fit on
+/// the grid either way.
+fn add_placement_ship(player: &mut Player, length: u8) -> Result<(), BattleshipError> {
+    player
+        .add_ship([0, 0], Direction::West, length, true)
+        .or_else(|_| player.add_ship([0, 0], Direction::North, length, true))
+}
+
+/// Selects a space on `opponent`'s grid if it's unchecked, and returns the outcome of the shot.
+///
+/// # Errors
+///
+/// Returns an error if no space exists at `pos`, or if the space at `pos` was already checked.
+fn shoot(opponent: &mut Player, pos: &[u8; 2]) -> Result<ShotOutcome, BattleshipError> {
+    let outcome = match opponent.select_space(pos)? {
+        None => ShotOutcome::Miss,
+        Some(ship_index) if opponent.sink_ship_if_all_hit(pos)? => {
+            let length = opponent.ships()[ship_index].len();
+
+            if opponent.all_ships_sunk() {
+                ShotOutcome::Won { ship_index, length }
+            } else {
+                ShotOutcome::Sunk { ship_index, length }
+            }
+        }
+        Some(ship_index) => ShotOutcome::Hit { ship_index },
+    };
+
+    Ok(outcome)
+}
+
+/// The state of a [`Game`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameState {
+    /// Players are placing their ships.
     Placement,
+    /// Players are taking turns selecting spaces.
     Active,
+    /// A player has sunk all of their opponent's ships.
     Complete,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::AdjacencyRule;
+    use rand::RngCore;
+
+    /// Returns an active game where the human player's ships are placed in separate rows.
+    fn active_game() -> Game {
+        let mut game = Game::new(GameSettings::defaults()).unwrap();
+
+        for (row, &len) in [0u8, 2, 4, 6].iter().zip(&game.settings.ships.clone()) {
+            let pos = (0..len).map(|x| [x, *row]).collect();
+            game.set_placement_ship(pos).unwrap();
+            game.place_ship().unwrap();
+        }
+
+        game.set_state_active().unwrap();
+        game
+    }
+
+    /// A strategy that places ships in separate rows and checks spaces in order.
+    struct OrderedStrategy;
+
+    impl Strategy for OrderedStrategy {
+        fn choose_shot(&mut self, opponent: &TargetView, _rng: &mut dyn RngCore) -> [u8; 2] {
+            opponent.unchecked_spaces()[0]
+        }
+
+        fn place_fleet(
+            &mut self,
+            _grid_size: [u8; 2],
+            ships: &[u8],
+            _adjacency: AdjacencyRule,
+            _rng: &mut dyn RngCore,
+        ) -> Vec<([u8; 2], Direction)> {
+            (0..ships.len() as u8)
+                .map(|i| ([0, i * 2], Direction::West))
+                .collect()
+        }
+    }
+
+    /// A strategy that always checks the top left space, whether or not it's been checked.
+    struct RepeatingStrategy;
+
+    impl Strategy for RepeatingStrategy {
+        fn choose_shot(&mut self, _opponent: &TargetView, _rng: &mut dyn RngCore) -> [u8; 2] {
+            [0, 0]
+        }
+
+        fn place_fleet(
+            &mut self,
+            grid_size: [u8; 2],
+            ships: &[u8],
+            adjacency: AdjacencyRule,
+            rng: &mut dyn RngCore,
+        ) -> Vec<([u8; 2], Direction)> {
+            OrderedStrategy.place_fleet(grid_size, ships, adjacency, rng)
+        }
+    }
+
+    #[test]
+    fn hotseat() {
+        let settings = GameSettings {
+            mode: GameMode::Hotseat,
+            ..GameSettings::defaults()
+        };
+        let mut game = Game::new(settings).unwrap();
+        assert!(game.is_hotseat());
+
+        for _ in 0..2 {
+            assert!(!game.active_player().is_cpu());
+
+            for (row, &len) in [0u8, 2, 4, 6].iter().zip(&game.settings.ships.clone()) {
+                let pos = (0..len).map(|x| [x, *row]).collect();
+                game.set_placement_ship(pos).unwrap();
+                game.place_ship().unwrap();
+            }
+
+            assert!(game.active_player_placed_all_ships());
+            game.switch_active_player();
+        }
+
+        game.set_state_active().unwrap();
+        assert_eq!(
+            game.select_space(&[0, 0]),
+            Ok(ShotOutcome::Hit { ship_index: 0 })
+        );
+        assert!(!Game::new(GameSettings::defaults()).unwrap().is_hotseat());
+    }
+
+    #[test]
+    fn custom_fleet() {
+        let settings = GameSettings {
+            ships: vec![5, 4, 3, 3, 2, 1],
+            adjacency: AdjacencyRule::Allowed,
+            seed: Some(7),
+            ..GameSettings::defaults()
+        };
+        let mut game = Game::new(settings).unwrap();
+
+        // Ships of the same length are placed separately, and a single space ship can be placed
+        // and rotated like any other.
+        for (row, &len) in [0u8, 2, 4, 6, 8, 9]
+            .iter()
+            .zip(&game.settings.ships.clone())
+        {
+            let pos = (9 - len..9).map(|x| [x, *row]).collect();
+            game.set_placement_ship(pos).unwrap();
+
+            if len == 1 {
+                game.rotate_ship().unwrap();
+            }
+
+            game.place_ship().unwrap();
+        }
+
+        assert!(game.active_player_placed_all_ships());
+        game.set_state_active().unwrap();
+
+        while !game.is_state_complete() {
+            let pos = game.suggested_check();
+            game.select_space(&pos).unwrap();
+
+            if !game.is_state_complete() {
+                game.switch_active_player();
+            }
+        }
+
+        assert_eq!(
+            Game::new(GameSettings {
+                ships: vec![1; 51],
+                adjacency: AdjacencyRule::NoOrthogonalTouch,
+                ..GameSettings::defaults()
+            })
+            .err(),
+            Some(BattleshipError::FleetDoesNotFit)
+        );
+    }
+
+    #[test]
+    fn tight_fleet() {
+        // Single space ships in every other space of every other row are the only placement, so
+        // random placement never finds it.
+        let settings = || GameSettings {
+            spaces: [7, 7],
+            ships: vec![1; 16],
+            adjacency: AdjacencyRule::NoTouchIncludingDiagonals,
+            seed: Some(0),
+            ..GameSettings::defaults()
+        };
+
+        let game = Game::new(settings()).unwrap();
+        assert_eq!(game.players[1].ships().len(), 16);
+
+        let strategies = [0, 1].map(|_| -> Option<Box<dyn Strategy>> {
+            Some(Box::new(DefaultStrategy::new(Difficulty::Normal)))
+        });
+        let game = Game::with_strategies(settings(), strategies).unwrap();
+        assert!(game.players.iter().all(|p| p.ships().len() == 16));
+    }
+
+    #[test]
+    fn adjacency_rule() {
+        let rules = [
+            AdjacencyRule::Allowed,
+            AdjacencyRule::NoOrthogonalTouch,
+            AdjacencyRule::NoTouchIncludingDiagonals,
+        ];
+
+        for adjacency in rules {
+            let settings = GameSettings {
+                adjacency,
+                seed: Some(3),
+                ..GameSettings::defaults()
+            };
+            let mut game = Game::new(settings).unwrap();
+            game.set_placement_ship(vec![[0, 0], [1, 0]]).unwrap();
+            game.place_ship().unwrap();
+
+            // The same rule applies to both players.
+            for player in game.players() {
+                assert_eq!(player.adjacency_rule(), adjacency);
+            }
+
+            // A ship touching the first one diagonally, and then one lying alongside it.
+            let diagonal = vec![[2, 1], [2, 2], [2, 3]];
+            let alongside = vec![[0, 1], [1, 1], [2, 1]];
+            let allowed = [
+                adjacency != AdjacencyRule::NoTouchIncludingDiagonals,
+                adjacency == AdjacencyRule::Allowed,
+            ];
+
+            for (pos, allowed) in [diagonal, alongside].into_iter().zip(allowed) {
+                game.set_placement_ship(pos).unwrap();
+
+                if allowed {
+                    assert_eq!(game.place_ship(), Ok(()));
+                    game.undo_placement().unwrap();
+                } else {
+                    assert_eq!(
+                        game.place_ship(),
+                        Err(BattleshipError::Adjacent { rule: adjacency })
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn non_square_grid() {
+        for spaces in [[8, 12], [12, 8], [20, 20]] {
+            let settings = GameSettings {
+                spaces,
+                seed: Some(5),
+                ..GameSettings::defaults()
+            };
+            let mut game = Game::with_strategies(
+                settings,
+                [
+                    Some(Box::new(DefaultStrategy::new(Difficulty::Hard))),
+                    Some(Box::new(DefaultStrategy::new(Difficulty::Normal))),
+                ],
+            )
+            .unwrap();
+            game.set_state_active().unwrap();
+
+            while !game.is_state_complete() {
+                let pos = game.suggested_check();
+                assert!(pos[0] < spaces[0] && pos[1] < spaces[1]);
+                game.select_space(&pos).unwrap();
+
+                if !game.is_state_complete() {
+                    game.switch_active_player();
+                }
+            }
+
+            // Every ship space of the loser was hit, and nothing else on the grid was marked as
+            // a hit.
+            let loser = game.inactive_player();
+            let hits = loser.spaces().iter().filter(|s| s.is_hit()).count();
+            assert_eq!(
+                hits,
+                game.settings
+                    .ships
+                    .iter()
+                    .map(|&l| l as usize)
+                    .sum::<usize>()
+            );
+        }
+    }
+
+    #[test]
+    fn with_strategies() {
+        let mut game = Game::with_strategies(
+            GameSettings::defaults(),
+            [
+                Some(Box::new(OrderedStrategy)),
+                Some(Box::new(DefaultStrategy::new(Difficulty::Hard))),
+            ],
+        )
+        .unwrap();
+
+        assert!(game.active_player().is_cpu() && game.inactive_player().is_cpu());
+        assert!(game.active_player_placed_all_ships());
+        assert_eq!(game.active_player().ships()[3].pos()[0], [0, 6]);
+
+        game.set_state_active().unwrap();
+
+        while !game.is_state_complete() {
+            let pos = game.suggested_check();
+            assert!(game.select_space(&pos).is_ok());
+
+            if !game.is_state_complete() {
+                game.switch_active_player();
+            }
+        }
+
+        assert!(game.get_winner().is_some());
+    }
+
+    #[test]
+    fn seed() {
+        let settings = || GameSettings {
+            seed: Some(42),
+            ..GameSettings::defaults()
+        };
+        let mut games = [
+            Game::new(settings()).unwrap(),
+            Game::new(settings()).unwrap(),
+        ];
+        let fleets = games
+            .iter()
+            .map(|g| {
+                g.players[1]
+                    .ships()
+                    .iter()
+                    .map(|s| s.pos().to_vec())
+                    .collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+
+        assert_eq!(fleets[0], fleets[1]);
+
+        for game in &mut games {
+            game.set_state_active().unwrap();
+            game.switch_active_player();
+        }
+
+        for _ in 0..20 {
+            let checks = games.each_mut().map(|g| g.suggested_check());
+            assert_eq!(checks[0], checks[1]);
+
+            for game in &mut games {
+                game.select_space(&checks[0]).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn place_ship_failure() {
+        let mut game = Game::new(GameSettings::defaults()).unwrap();
+        game.settings.ships[1] = 11;
+        game.set_placement_ship(vec![[0, 0], [1, 0]]).unwrap();
+
+        // The next ship can't be added, so the placed ship is lifted again with nothing to undo.
+        assert_eq!(game.place_ship(), Err(BattleshipError::OutOfBounds));
+        assert_eq!(game.active_player().ships().len(), 1);
+        assert!(game.active_player().placement_ship().is_ok());
+        assert_eq!(game.undo_placement(), Err(BattleshipError::NothingToUndo));
+    }
+
+    #[test]
+    fn undo_placement() {
+        let mut game = Game::new(GameSettings::defaults()).unwrap();
+        let row = |len: u8, y: u8| (0..len).map(|x| [x, y]).collect::<Vec<[u8; 2]>>();
+        assert_eq!(game.undo_placement(), Err(BattleshipError::NothingToUndo));
+
+        game.set_placement_ship(row(2, 0)).unwrap();
+        game.place_ship().unwrap();
+        game.set_placement_ship(row(3, 2)).unwrap();
+        game.place_ship().unwrap();
+        assert_eq!(game.active_player().ships().len(), 3);
+
+        // Undoing the second placement lifts that ship where it was placed.
+        assert!(game.undo_placement().is_ok());
+        assert_eq!(game.active_player().ships().len(), 2);
+        assert_eq!(
+            game.active_player().placement_ship().unwrap().pos(),
+            row(3, 2)
+        );
+        assert!(game.redo_placement().is_ok());
+        assert_eq!(game.redo_placement(), Err(BattleshipError::NothingToRedo));
+        assert_eq!(game.active_player().ships().len(), 3);
+
+        // Lifting the first ship removes the unplaced third ship until the first is placed again.
+        assert!(game.lift_ship(&[1, 0]).is_ok());
+        assert_eq!(game.active_player().ships().len(), 2);
+        assert_eq!(
+            game.lift_ship(&[0, 2]),
+            Err(BattleshipError::ShipAlreadyLifted)
+        );
+        game.set_placement_ship(row(2, 8)).unwrap();
+        game.place_ship().unwrap();
+        assert_eq!(game.active_player().placement_ship().unwrap().len(), 4);
+
+        for (y, len) in [(4, 4), (6, 5)] {
+            assert_eq!(game.confirm_fleet(), Err(BattleshipError::IncompleteFleet));
+            game.set_placement_ship(row(len, y)).unwrap();
+            game.place_ship().unwrap();
+        }
+
+        assert!(game.active_player_placed_all_ships());
+        assert!(!game.active_player_confirmed_fleet());
+        assert!(game.confirm_fleet().is_ok());
+        assert!(game.active_player_confirmed_fleet());
+        assert_eq!(game.undo_placement(), Err(BattleshipError::FleetConfirmed));
+        assert_eq!(
+            game.lift_ship(&[0, 8]),
+            Err(BattleshipError::FleetConfirmed)
+        );
+
+        // Only the confirmed positions of the human player's ships are recorded.
+        let placements = game
+            .replay()
+            .events()
+            .iter()
+            .filter(|e| e.player == 0)
+            .map(|e| e.action.clone())
+            .collect::<Vec<ReplayAction>>();
+        let expected = [row(2, 8), row(3, 2), row(4, 4), row(5, 6)]
+            .into_iter()
+            .map(ReplayAction::Placement)
+            .collect::<Vec<ReplayAction>>();
+        assert_eq!(placements, expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn save_load_placement() {
+        let mut game = Game::new(GameSettings {
+            ships: vec![5, 4, 3, 3, 2],
+            ..GameSettings::defaults()
+        })
+        .unwrap();
+
+        for row in [0u8, 2] {
+            let len = game.active_player().placement_ship().unwrap().len() as u8;
+            game.set_placement_ship((0..len).map(|x| [x, row]).collect())
+                .unwrap();
+            game.place_ship().unwrap();
+        }
+
+        let mut saved = vec![];
+        game.save(&mut saved).unwrap();
+
+        // The rest of the fleet can still be placed, one ship at a time or all at once.
+        let mut placed = Game::load(saved.as_slice()).unwrap();
+
+        for row in [4u8, 6, 8] {
+            let len = placed.active_player().placement_ship().unwrap().len() as u8;
+            placed
+                .set_placement_ship((0..len).map(|x| [x, row]).collect())
+                .unwrap();
+            assert_eq!(placed.place_ship(), Ok(()));
+        }
+
+        assert!(placed.active_player_placed_all_ships());
+
+        let mut auto_placed = Game::load(saved.as_slice()).unwrap();
+        assert_eq!(
+            auto_placed.auto_place_fleet(PlacementOptions::default()),
+            Ok(())
+        );
+        assert!(auto_placed.active_player_placed_all_ships());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn save_load() {
+        let mut game = active_game();
+        game.select_space(&[0, 0]).unwrap();
+        game.switch_active_player();
+        game.move_grid_cursor(Direction::South).unwrap();
+
+        let mut saved = vec![];
+        game.save(&mut saved).unwrap();
+        let mut loaded = Game::load(saved.as_slice()).unwrap();
+        let mut resaved = vec![];
+        loaded.save(&mut resaved).unwrap();
+
+        assert_eq!(saved, resaved);
+        assert_eq!(loaded.turn(), 1);
+        assert_eq!(loaded.active_player().grid_cursor(), &[0, 1]);
+        assert!(loaded.is_state_active());
+
+        let pos = loaded.suggested_check();
+        assert!(loaded.select_space(&pos).is_ok());
+    }
+
+    #[test]
+    fn verify_remote_fleet() {
+        let settings = GameSettings {
+            mode: GameMode::Network { host: true },
+            ships: vec![2],
+            ..GameSettings::defaults()
+        };
+        let mut game = Game::new(settings).unwrap();
+        game.set_placement_ship(vec![[0, 0], [1, 0]]).unwrap();
+        game.place_ship().unwrap();
+        assert!(game.fleet_commitment().is_some());
+
+        let reveal = FleetReveal::with_salt([3; 16], vec![vec![[5, 5], [5, 6]]]);
+        game.set_remote_ready(reveal.commitment()).unwrap();
+        game.set_state_active().unwrap();
+        assert_eq!(
+            game.verify_remote_fleet(&reveal),
+            Err(BattleshipError::WrongState {
+                expected: GameState::Complete,
+                actual: GameState::Active,
+            })
+        );
+
+        let won = ShotOutcome::Won {
+            ship_index: 0,
+            length: 2,
+        };
+        game.record_remote_outcome(&[0, 0], won).unwrap();
+        assert!(game.is_state_complete());
+
+        assert_eq!(
+            game.verify_remote_fleet(&reveal),
+            Ok(vec![RevealMismatch::Shot([0, 0])])
+        );
+
+        let moved = FleetReveal::with_salt([3; 16], vec![vec![[0, 0], [0, 1]]]);
+        assert_eq!(
+            game.verify_remote_fleet(&moved),
+            Ok(vec![
+                RevealMismatch::Commitment,
+                RevealMismatch::Shot([0, 0])
+            ])
+        );
+
+        let invalid = FleetReveal::with_salt([3; 16], vec![vec![[0, 0], [2, 0]]]);
+        assert_eq!(
+            game.verify_remote_fleet(&invalid),
+            Ok(vec![
+                RevealMismatch::Commitment,
+                RevealMismatch::InvalidFleet
+            ])
+        );
+    }
+
+    #[test]
+    fn salvo() {
+        let settings = GameSettings {
+            shot_rule: ShotRule::Salvo,
+            ..GameSettings::defaults()
+        };
+        let mut game =
+            Game::with_strategies(settings, [None, Some(Box::new(OrderedStrategy))]).unwrap();
+
+        for (row, &len) in [0u8, 2, 4, 6].iter().zip(&game.settings.ships.clone()) {
+            let pos = (0..len).map(|x| [x, *row]).collect();
+            game.set_placement_ship(pos).unwrap();
+            game.place_ship().unwrap();
+        }
+
+        game.set_state_active().unwrap();
+        assert_eq!(game.shots_per_turn(), 4);
+        assert_eq!(
+            game.fire_salvo(&[[0, 0]]),
+            Err(BattleshipError::WrongShotCount {
+                expected: 4,
+                actual: 1,
+            })
+        );
+        assert_eq!(
+            game.fire_salvo(&[[0, 0], [0, 1], [0, 2], [0, 0]]),
+            Err(BattleshipError::AlreadyChecked)
+        );
+        assert!(game.inactive_player().space(&[0, 0]).is_unchecked());
+
+        // The CPU player's volley doesn't repeat spaces, even though none of them are checked
+        // until it's fired.
+        game.switch_active_player();
+        let volley = game.suggested_salvo();
+        assert_eq!(volley, vec![[0, 0], [0, 1], [0, 2], [0, 3]]);
+        assert_eq!(
+            game.fire_salvo(&volley),
+            Ok(vec![
+                ShotOutcome::Hit { ship_index: 0 },
+                ShotOutcome::Miss,
+                ShotOutcome::Hit { ship_index: 1 },
+                ShotOutcome::Miss,
+            ])
+        );
+
+        // Losing a ship costs the human player a shot.
+        game.select_space(&[1, 0]).unwrap();
+        assert_eq!(game.sunk_ships(0), vec![true, false, false, false]);
+        assert_eq!(game.sunk_ships(1), vec![false; 4]);
+        game.switch_active_player();
+        assert_eq!(game.shots_per_turn(), 3);
+
+        game.settings.shot_rule = ShotRule::Fixed(2);
+        assert_eq!(game.shots_per_turn(), 2);
+        assert_eq!(game.suggested_salvo().len(), 2);
+    }
+
+    #[test]
+    fn select_space() {
+        let mut game = active_game();
+        let ships = game
+            .inactive_player()
+            .ships()
+            .iter()
+            .map(|s| s.pos().to_vec())
+            .collect::<Vec<_>>();
+        let miss = game
+            .inactive_player()
+            .spaces()
+            .iter()
+            .map(|s| *s.pos())
+            .find(|p| !ships.iter().any(|s| s.contains(p)))
+            .unwrap();
+
+        assert_eq!(game.select_space(&miss), Ok(ShotOutcome::Miss));
+        assert_eq!(
+            game.select_space(&miss),
+            Err(BattleshipError::AlreadyChecked)
+        );
+
+        for (i, ship) in ships.iter().enumerate() {
+            for (j, pos) in ship.iter().enumerate() {
+                let expected = if j < ship.len() - 1 {
+                    ShotOutcome::Hit { ship_index: i }
+                } else if i < ships.len() - 1 {
+                    ShotOutcome::Sunk {
+                        ship_index: i,
+                        length: ship.len(),
+                    }
+                } else {
+                    ShotOutcome::Won {
+                        ship_index: i,
+                        length: ship.len(),
+                    }
+                };
+
+                assert_eq!(game.select_space(pos), Ok(expected));
+            }
+        }
+
+        assert!(game.is_state_complete());
+        assert_eq!(
+            game.select_space(&miss),
+            Err(BattleshipError::WrongState {
+                expected: GameState::Active,
+                actual: GameState::Complete,
+            })
+        );
+    }
+
+    #[test]
+    fn network_mark_around_sunk() {
+        let settings = GameSettings {
+            mode: GameMode::Network { host: true },
+            ships: vec![2],
+            mark_around_sunk: true,
+            ..GameSettings::defaults()
+        };
+        let mut game = Game::new(settings).unwrap();
+        game.set_placement_ship(vec![[0, 0], [1, 0]]).unwrap();
+        game.place_ship().unwrap();
+        game.set_remote_ready(FleetReveal::with_salt([3; 16], vec![]).commitment())
+            .unwrap();
+        game.set_state_active().unwrap();
+        game.record_remote_outcome(&[5, 5], ShotOutcome::Miss)
+            .unwrap();
+        game.switch_active_player();
+
+        // The remote player can't know that the spaces around the sunk ship are empty, so they
+        // stay unchecked for them to select.
+        game.select_space(&[0, 0]).unwrap();
+        assert!(game.select_space(&[1, 0]).unwrap().is_sunk());
+        assert!(game.players()[0].space(&[0, 1]).is_unchecked());
+        assert!(game.players()[0].space(&[2, 0]).is_unchecked());
+    }
+
+    #[test]
+    fn invalid_strategy_shots() {
+        let settings = GameSettings {
+            shot_rule: ShotRule::Salvo,
+            ..GameSettings::defaults()
+        };
+        let mut game = Game::with_strategies(
+            settings,
+            [
+                Some(Box::new(RepeatingStrategy)),
+                Some(Box::new(OrderedStrategy)),
+            ],
+        )
+        .unwrap();
+        game.set_state_active().unwrap();
+
+        // The strategy's repeated choice is replaced by the built-in strategy's.
+        let volley = game.suggested_salvo();
+        assert_eq!(volley.len(), 4);
+        assert_eq!(volley[0], [0, 0]);
+        assert!(!volley[1..].contains(&[0, 0]));
+        assert!(game.fire_salvo(&volley).is_ok());
+        assert_ne!(game.suggested_check(), [0, 0]);
+    }
+
+    #[test]
+    fn target_view() {
+        let settings = GameSettings {
+            mode: GameMode::Network { host: true },
+            ships: vec![2, 3],
+            ..GameSettings::defaults()
+        };
+        let mut game = Game::new(settings).unwrap();
+        game.set_placement_ship(vec![[0, 0], [1, 0]]).unwrap();
+        game.place_ship().unwrap();
+        game.set_placement_ship(vec![[0, 2], [1, 2], [2, 2]])
+            .unwrap();
+        game.place_ship().unwrap();
+        game.set_remote_ready(FleetReveal::with_salt([3; 16], vec![]).commitment())
+            .unwrap();
+        game.set_state_active().unwrap();
+
+        // The remote player's ships aren't known, but the outcomes they reported are.
+        for (pos, outcome) in [
+            ([5, 5], ShotOutcome::Hit { ship_index: 1 }),
+            ([0, 0], ShotOutcome::Hit { ship_index: 0 }),
+            (
+                [0, 1],
+                ShotOutcome::Sunk {
+                    ship_index: 0,
+                    length: 2,
+                },
+            ),
+        ] {
+            game.record_remote_outcome(&pos, outcome).unwrap();
+        }
+
+        let view = game.target_view();
+        assert!(game.inactive_player().ships().is_empty());
+        assert_eq!(view.ship_lengths(), &[2, 3]);
+        assert_eq!(view.sunk_ship(0), Some(&[[0, 0], [0, 1]][..]));
+        assert_eq!(view.sunk_ship(1), None);
+        assert_eq!(view.remaining_lengths(), vec![3]);
+        assert_eq!(
+            view.suggested_checks(),
+            vec![[5, 4], [6, 5], [5, 6], [4, 5]]
+        );
+    }
+}
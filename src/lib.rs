@@ -0,0 +1,45 @@
+//! The rules engine for a game of Battleship.
+//!
+//! The engine has no dependency on any particular frontend.  A [`Game`] is created from
+//! [`GameSettings`], and is then driven by placing ships during the placement state and by
+//! selecting spaces on the inactive player's grid during the active state.  Computer-controlled
+//! players place their ships and choose spaces using a [`Strategy`], and a game can be played
+//! against another instance of the game over a [`Connection`].
+//!
+//! The Piston frontend is built as the `rust-battleship` binary, which is enabled by the `gui`
+//! feature.  Consumers that only need the engine can disable default features to avoid any
+//! graphics dependencies.
+
+#![warn(missing_docs)]
+
+mod commitment;
+mod direction;
+mod error;
+mod external;
+mod game;
+mod net;
+mod outcome;
+mod placement;
+mod player;
+mod replay;
+mod settings;
+mod ship;
+mod space;
+mod strategy;
+mod target;
+
+pub use crate::commitment::{FleetCommitment, FleetReveal, RevealMismatch};
+pub use crate::direction::Direction;
+pub use crate::error::BattleshipError;
+pub use crate::external::ExternalStrategy;
+pub use crate::game::{Game, GameState};
+pub use crate::net::{Connection, Message, NetError, PROTOCOL_VERSION};
+pub use crate::outcome::ShotOutcome;
+pub use crate::placement::PlacementOptions;
+pub use crate::player::Player;
+pub use crate::replay::{Replay, ReplayAction, ReplayEvent};
+pub use crate::settings::{AdjacencyRule, Difficulty, GameMode, GameSettings, ShotRule};
+pub use crate::ship::{Ship, ShipState};
+pub use crate::space::{Space, SpaceState};
+pub use crate::strategy::{DefaultStrategy, Strategy};
+pub use crate::target::TargetView;
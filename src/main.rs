@@ -1,14 +1,58 @@
 mod app;
-mod direction;
-mod game;
-mod player;
-mod settings;
-mod ship;
-mod space;
+mod config;
+mod parse;
+mod textures;
+mod viewer;
+
+use app::AppSettings;
+use config::{Config, Launch};
+use rust_battleship::{Connection, Replay};
+use std::{env, fs::File, io::BufReader, process};
 
 fn main() {
-    let settings = settings::AppSettings { space_size: 20 };
-    let mut app = app::App::new(&settings);
+    let Config {
+        space_size,
+        game: game_settings,
+        launch,
+    } = Config::load(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("error: {}\n\n{}", e, config::USAGE);
+        process::exit(2);
+    });
+    let settings = AppSettings {
+        space_size,
+        save_file: "battleship-save.json".into(),
+        replay_file: "battleship-replay.json".into(),
+    };
+
+    match launch {
+        Launch::Local => app::App::new(&settings, game_settings, None).init(),
+        Launch::Host(addr) => {
+            println!("waiting for another player to join on {}", addr);
+            let connection = Connection::host(&addr, &game_settings).unwrap_or_else(|e| {
+                eprintln!("failed to host game on {}: {}", addr, e);
+                process::exit(1);
+            });
+
+            app::App::new(&settings, game_settings, Some(connection)).init();
+        }
+        Launch::Join(addr) => {
+            let (connection, game_settings) = Connection::join(&addr).unwrap_or_else(|e| {
+                eprintln!("failed to join game at {}: {}", addr, e);
+                process::exit(1);
+            });
+
+            app::App::new(&settings, game_settings, Some(connection)).init();
+        }
+        Launch::Replay(path) => {
+            let replay = File::open(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|f| Replay::load(BufReader::new(f)).map_err(|e| e.to_string()))
+                .unwrap_or_else(|e| {
+                    eprintln!("failed to load replay from {}: {}", path.display(), e);
+                    process::exit(1);
+                });
 
-    app.init();
+            viewer::ReplayViewer::new(&settings, replay).init();
+        }
+    }
 }
@@ -0,0 +1,539 @@
+use crate::{
+    commitment::{self, FleetCommitment, FleetReveal},
+    error::BattleshipError,
+    outcome::ShotOutcome,
+    settings::{AdjacencyRule, GameMode, GameSettings},
+};
+use std::{
+    error, fmt,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    str::FromStr,
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+/// The version of the protocol, which must match between both sides.
+pub const PROTOCOL_VERSION: u32 = 3;
+
+/// A message sent between two instances of the game.
+///
+/// Each message is sent as a single line of space-separated words.  Both sides first send
+/// `HELLO <version>`, and the host then sends `SETTINGS <columns> <rows> <adjacency> <ship
+/// lengths...>` to the joining side, where the adjacency rule is `ALLOWED`, `NO-ORTHOGONAL` or
+/// `NO-TOUCH`.  During placement, each side sends `READY <commitment>` once their ships are
+/// placed, where the commitment is a hex SHA-256 hash of their fleet and a secret salt.  During the
+/// game the active player sends `SHOT <x> <y>` and the other side answers with the outcome of
+/// [`Game::select_space`](crate::Game::select_space) on their own grid: `RESULT MISS`,
+/// `RESULT HIT <ship>`, `RESULT SUNK <ship> <length>` or `RESULT WON <ship> <length>`.
+///
+/// Ship positions are only sent once the game is complete, when each side sends
+/// `REVEAL <salt> <ship>...`, with each ship's positions as `x,y,x,y,...`, so that the other side
+/// can check them against the commitment and the reported shot outcomes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    /// Opens the connection, giving the sender's protocol version.
+    Hello(u32),
+    /// The grid size, ship lengths and adjacency rule chosen by the host.
+    Settings {
+        /// The number of columns and rows in each player's grid.
+        spaces: [u8; 2],
+        /// The lengths of the ships each player places.
+        ships: Vec<u8>,
+        /// Whether each player's ships may be placed next to each other.
+        adjacency: AdjacencyRule,
+    },
+    /// The sender has placed all of their ships, and committed to their positions.
+    Ready(FleetCommitment),
+    /// The sender selected a space on the receiver's grid.
+    Shot([u8; 2]),
+    /// The outcome of the receiver's last shot.
+    Result(ShotOutcome),
+    /// The sender's fleet and salt, sent once the game is complete.
+    Reveal(FleetReveal),
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Message::Hello(version) => write!(f, "HELLO {}", version),
+            Message::Settings {
+                spaces,
+                ships,
+                adjacency,
+            } => {
+                write!(
+                    f,
+                    "SETTINGS {} {} {}",
+                    spaces[0],
+                    spaces[1],
+                    adjacency.keyword()
+                )?;
+
+                for len in ships {
+                    write!(f, " {}", len)?;
+                }
+
+                Ok(())
+            }
+            Message::Ready(commitment) => write!(f, "READY {}", commitment),
+            Message::Shot(pos) => write!(f, "SHOT {} {}", pos[0], pos[1]),
+            Message::Result(outcome) => match *outcome {
+                ShotOutcome::Miss => write!(f, "RESULT MISS"),
+                ShotOutcome::Hit { ship_index } => write!(f, "RESULT HIT {}", ship_index),
+                ShotOutcome::Sunk { ship_index, length } => {
+                    write!(f, "RESULT SUNK {} {}", ship_index, length)
+                }
+                ShotOutcome::Won { ship_index, length } => {
+                    write!(f, "RESULT WON {} {}", ship_index, length)
+                }
+            },
+            Message::Reveal(reveal) => {
+                write!(f, "REVEAL {}", commitment::to_hex(reveal.salt()))?;
+
+                for ship in reveal.ships() {
+                    let pos = ship
+                        .iter()
+                        .flat_map(|p| [p[0].to_string(), p[1].to_string()])
+                        .collect::<Vec<_>>();
+                    write!(f, " {}", pos.join(","))?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl FromStr for Message {
+    type Err = NetError;
+
+    fn from_str(line: &str) -> Result<Message, NetError> {
+        let invalid = || NetError::InvalidMessage(line.to_string());
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let numbers = |words: &[&str]| -> Result<Vec<u8>, NetError> {
+            words
+                .iter()
+                .map(|w| w.parse().map_err(|_| invalid()))
+                .collect()
+        };
+        let index = |word: &str| word.parse::<usize>().map_err(|_| invalid());
+
+        match words.as_slice() {
+            ["HELLO", version] => Ok(Message::Hello(version.parse().map_err(|_| invalid())?)),
+            ["SETTINGS", columns, rows, adjacency, ships @ ..] => Ok(Message::Settings {
+                spaces: [
+                    columns.parse().map_err(|_| invalid())?,
+                    rows.parse().map_err(|_| invalid())?,
+                ],
+                ships: numbers(ships)?,
+                adjacency: match *adjacency {
+                    "ALLOWED" => AdjacencyRule::Allowed,
+                    "NO-ORTHOGONAL" => AdjacencyRule::NoOrthogonalTouch,
+                    "NO-TOUCH" => AdjacencyRule::NoTouchIncludingDiagonals,
+                    _ => return Err(invalid()),
+                },
+            }),
+            ["READY", commitment] => Ok(Message::Ready(commitment.parse().map_err(|_| invalid())?)),
+            ["SHOT", x, y] => {
+                let pos = numbers(&[x, y])?;
+                Ok(Message::Shot([pos[0], pos[1]]))
+            }
+            ["RESULT", "MISS"] => Ok(Message::Result(ShotOutcome::Miss)),
+            ["RESULT", "HIT", ship] => Ok(Message::Result(ShotOutcome::Hit {
+                ship_index: index(ship)?,
+            })),
+            ["RESULT", "SUNK", ship, length] => Ok(Message::Result(ShotOutcome::Sunk {
+                ship_index: index(ship)?,
+                length: index(length)?,
+            })),
+            ["RESULT", "WON", ship, length] => Ok(Message::Result(ShotOutcome::Won {
+                ship_index: index(ship)?,
+                length: index(length)?,
+            })),
+            ["REVEAL", salt, ships @ ..] => {
+                let salt = commitment::from_hex(salt).map_err(|_| invalid())?;
+                let ships = ships
+                    .iter()
+                    .map(|ship| {
+                        let pos = numbers(&ship.split(',').collect::<Vec<_>>())?;
+
+                        match pos.len() % 2 {
+                            0 => Ok(pos.chunks(2).map(|p| [p[0], p[1]]).collect()),
+                            _ => Err(invalid()),
+                        }
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                Ok(Message::Reveal(FleetReveal::with_salt(salt, ships)))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// An error returned by a network operation.
+#[derive(Debug)]
+pub enum NetError {
+    /// Reading from or writing to the connection failed.
+    Io(io::Error),
+    /// The other side closed the connection.
+    Disconnected,
+    /// A line was received that isn't a valid message.
+    InvalidMessage(String),
+    /// A valid message was received when a different one was expected.
+    UnexpectedMessage(Message),
+    /// The other side uses a different version of the protocol.
+    VersionMismatch {
+        /// The version of the protocol used by this side.
+        local: u32,
+        /// The version of the protocol used by the other side.
+        remote: u32,
+    },
+    /// A message from the other side couldn't be applied to the game.
+    Game(BattleshipError),
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetError::Io(e) => write!(f, "connection error: {}", e),
+            NetError::Disconnected => write!(f, "the other player disconnected"),
+            NetError::InvalidMessage(line) => write!(f, "received invalid message '{}'", line),
+            NetError::UnexpectedMessage(message) => {
+                write!(f, "received unexpected message '{}'", message)
+            }
+            NetError::VersionMismatch { local, remote } => write!(
+                f,
+                "protocol version {} doesn't match the other player's version {}",
+                local, remote
+            ),
+            NetError::Game(e) => write!(f, "invalid message from the other player: {}", e),
+        }
+    }
+}
+
+impl error::Error for NetError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            NetError::Io(e) => Some(e),
+            NetError::Game(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for NetError {
+    fn from(e: io::Error) -> NetError {
+        NetError::Io(e)
+    }
+}
+
+impl From<BattleshipError> for NetError {
+    fn from(e: BattleshipError) -> NetError {
+        NetError::Game(e)
+    }
+}
+
+/// A connection to another instance of the game.
+///
+/// Incoming messages are read on a background thread, so they can be polled with
+/// [`Connection::try_recv`] without blocking a frontend's event loop.
+pub struct Connection {
+    stream: TcpStream,
+    messages: Receiver<Result<Message, NetError>>,
+}
+
+impl Connection {
+    /// Listens on `addr` for another instance to join, and sends it the grid size, ships and
+    /// adjacency rule from `settings`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection failed or the other instance's protocol version
+    /// doesn't match.
+    pub fn host<A: ToSocketAddrs>(
+        addr: A,
+        settings: &GameSettings,
+    ) -> Result<Connection, NetError> {
+        let listener = TcpListener::bind(addr)?;
+
+        Connection::accept(&listener, settings)
+    }
+
+    /// Accepts the next instance to connect to `listener`, and sends it the grid size, ships and
+    /// adjacency rule from `settings`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection failed or the other instance's protocol version
+    /// doesn't match.
+    pub fn accept(listener: &TcpListener, settings: &GameSettings) -> Result<Connection, NetError> {
+        let (stream, _) = listener.accept()?;
+        let mut connection = Connection::handshake(stream)?;
+
+        connection.send(&Message::Settings {
+            spaces: settings.spaces,
+            ships: settings.ships.clone(),
+            adjacency: settings.adjacency,
+        })?;
+
+        Ok(connection)
+    }
+
+    /// Joins the game hosted at `addr`, and returns the connection along with the settings for
+    /// the joining side's game.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection failed, the host's protocol version doesn't match, or
+    /// the host didn't send the game's settings or sent a fleet that doesn't fit on the grid.
+    pub fn join<A: ToSocketAddrs>(addr: A) -> Result<(Connection, GameSettings), NetError> {
+        let connection = Connection::handshake(TcpStream::connect(addr)?)?;
+
+        match connection.recv()? {
+            Message::Settings {
+                spaces,
+                ships,
+                adjacency,
+            } => {
+                let settings = GameSettings {
+                    spaces,
+                    ships,
+                    adjacency,
+                    mode: GameMode::Network { host: false },
+                    ..GameSettings::defaults()
+                };
+                settings.validate()?;
+
+                Ok((connection, settings))
+            }
+            message => Err(NetError::UnexpectedMessage(message)),
+        }
+    }
+
+    /// Starts reading messages from `stream`, and exchanges protocol versions.
+    fn handshake(stream: TcpStream) -> Result<Connection, NetError> {
+        // Messages are small and each one waits for a reply, so send them straight away.
+        stream.set_nodelay(true)?;
+
+        let reader = BufReader::new(stream.try_clone()?);
+        let (sender, messages) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let message = line.map_err(NetError::from).and_then(|l| l.parse());
+                let failed = message.is_err();
+
+                if sender.send(message).is_err() || failed {
+                    return;
+                }
+            }
+        });
+
+        let mut connection = Connection { stream, messages };
+        connection.send(&Message::Hello(PROTOCOL_VERSION))?;
+
+        match connection.recv()? {
+            Message::Hello(PROTOCOL_VERSION) => Ok(connection),
+            Message::Hello(remote) => Err(NetError::VersionMismatch {
+                local: PROTOCOL_VERSION,
+                remote,
+            }),
+            message => Err(NetError::UnexpectedMessage(message)),
+        }
+    }
+
+    /// Sends `message` to the other instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message couldn't be written to the connection.
+    pub fn send(&mut self, message: &Message) -> Result<(), NetError> {
+        writeln!(self.stream, "{}", message)?;
+
+        Ok(())
+    }
+
+    /// Waits for the next message from the other instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection failed or was closed, or an invalid message was
+    /// received.
+    pub fn recv(&self) -> Result<Message, NetError> {
+        self.messages.recv().map_err(|_| NetError::Disconnected)?
+    }
+
+    /// Returns the next message from the other instance if one has arrived, without waiting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection failed or was closed, or an invalid message was
+    /// received.
+    pub fn try_recv(&self) -> Result<Option<Message>, NetError> {
+        match self.messages.try_recv() {
+            Ok(message) => message.map(Some),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(NetError::Disconnected),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{commitment::RevealMismatch, game::Game};
+
+    #[test]
+    fn message_round_trip() {
+        let messages = [
+            Message::Hello(PROTOCOL_VERSION),
+            Message::Settings {
+                spaces: [10, 8],
+                ships: vec![2, 3, 4],
+                adjacency: AdjacencyRule::NoTouchIncludingDiagonals,
+            },
+            Message::Ready(FleetReveal::with_salt([1; 16], vec![]).commitment()),
+            Message::Shot([3, 9]),
+            Message::Result(ShotOutcome::Miss),
+            Message::Result(ShotOutcome::Hit { ship_index: 1 }),
+            Message::Result(ShotOutcome::Sunk {
+                ship_index: 0,
+                length: 2,
+            }),
+            Message::Result(ShotOutcome::Won {
+                ship_index: 3,
+                length: 5,
+            }),
+            Message::Reveal(FleetReveal::with_salt(
+                [2; 16],
+                vec![vec![[0, 0], [1, 0]], vec![[4, 2], [4, 3], [4, 4]]],
+            )),
+        ];
+
+        for message in messages {
+            assert_eq!(message.to_string().parse::<Message>().unwrap(), message);
+        }
+
+        assert!("SETTINGS 10 10 2 3".parse::<Message>().is_err());
+        assert!("SHOT 3".parse::<Message>().is_err());
+        assert!("SHOT 3 x".parse::<Message>().is_err());
+        assert!("FIRE 3 4".parse::<Message>().is_err());
+        assert!("READY 1234".parse::<Message>().is_err());
+        assert!("REVEAL 00 1,2".parse::<Message>().is_err());
+    }
+
+    /// Places the active player's ships in separate rows.
+    fn place_ships(game: &mut Game) {
+        for (row, &len) in [0u8, 2, 4, 6].iter().zip(&game.settings().ships.clone()) {
+            let pos = (0..len).map(|x| [x, *row]).collect();
+            game.set_placement_ship(pos).unwrap();
+            game.place_ship().unwrap();
+        }
+    }
+
+    /// Plays this side's turns of a network game, checking spaces in order, and returns the
+    /// winner and any mismatches found in the other side's revealed fleet.
+    ///
+    /// If `lie` is true, this side reports the first shot that hits one of its ships as a miss.
+    fn play(
+        mut game: Game,
+        mut connection: Connection,
+        local: usize,
+        mut lie: bool,
+    ) -> (usize, Vec<RevealMismatch>) {
+        assert_eq!(game.turn(), local);
+        place_ships(&mut game);
+
+        let commitment = game.fleet_commitment().unwrap();
+        connection.send(&Message::Ready(commitment)).unwrap();
+
+        match connection.recv().unwrap() {
+            Message::Ready(commitment) => game.set_remote_ready(commitment).unwrap(),
+            message => panic!("unexpected message {:?}", message),
+        }
+
+        game.set_state_active().unwrap();
+
+        while !game.is_state_complete() {
+            if game.turn() == local {
+                let pos = game.target_view().unchecked_spaces()[0];
+                connection.send(&Message::Shot(pos)).unwrap();
+
+                match connection.recv().unwrap() {
+                    Message::Result(outcome) => game.record_remote_outcome(&pos, outcome).unwrap(),
+                    message => panic!("unexpected message {:?}", message),
+                }
+            } else {
+                match connection.recv().unwrap() {
+                    Message::Shot(pos) => {
+                        let mut outcome = game.select_space(&pos).unwrap();
+
+                        if lie && outcome.is_hit() {
+                            outcome = ShotOutcome::Miss;
+                            lie = false;
+                        }
+
+                        connection.send(&Message::Result(outcome)).unwrap();
+                    }
+                    message => panic!("unexpected message {:?}", message),
+                }
+            }
+
+            if !game.is_state_complete() {
+                game.switch_active_player();
+            }
+        }
+
+        let reveal = game.fleet_reveal().unwrap().clone();
+        connection.send(&Message::Reveal(reveal)).unwrap();
+
+        let mismatches = match connection.recv().unwrap() {
+            Message::Reveal(reveal) => game.verify_remote_fleet(&reveal).unwrap(),
+            message => panic!("unexpected message {:?}", message),
+        };
+
+        (game.get_winner().unwrap(), mismatches)
+    }
+
+    /// Plays a network game over loopback, and returns the result for each side.
+    fn loopback_game(lie: [bool; 2]) -> [(usize, Vec<RevealMismatch>); 2] {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let settings = GameSettings {
+            mode: GameMode::Network { host: true },
+            ..GameSettings::defaults()
+        };
+
+        let joiner = thread::spawn(move || {
+            let (connection, settings) = Connection::join(addr).unwrap();
+            assert_eq!(settings.ships, GameSettings::defaults().ships);
+
+            play(Game::new(settings).unwrap(), connection, 1, lie[1])
+        });
+
+        let connection = Connection::accept(&listener, &settings).unwrap();
+        let game = Game::new(settings).unwrap();
+        assert!(game.is_network());
+        assert!(game.inactive_player().ships().is_empty());
+
+        [play(game, connection, 0, lie[0]), joiner.join().unwrap()]
+    }
+
+    #[test]
+    fn loopback() {
+        // Both sides check spaces in the same order, so the host, who shoots first, wins.
+        assert_eq!(loopback_game([false, false]), [(0, vec![]), (0, vec![])]);
+    }
+
+    #[test]
+    fn loopback_lie() {
+        // The joiner's own game still knows the first shot was a hit, so the game plays out the
+        // same, but the host finds the lie once the joiner's fleet is revealed.
+        assert_eq!(
+            loopback_game([false, true]),
+            [(0, vec![RevealMismatch::Shot([0, 0])]), (0, vec![])]
+        );
+    }
+}
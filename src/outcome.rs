@@ -0,0 +1,48 @@
+/// The result of selecting a space on an opponent's grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShotOutcome {
+    /// No ship occupied the space.
+    Miss,
+    /// A ship occupied the space, and still has spaces that haven't been hit.
+    Hit {
+        /// The index of the ship that was hit.
+        ship_index: usize,
+    },
+    /// A ship occupied the space, and every space it occupies has now been hit.
+    Sunk {
+        /// The index of the ship that was sunk.
+        ship_index: usize,
+        /// The length of the ship that was sunk.
+        length: usize,
+    },
+    /// The last of the opponent's ships was sunk, winning the game.
+    Won {
+        /// The index of the ship that was sunk.
+        ship_index: usize,
+        /// The length of the ship that was sunk.
+        length: usize,
+    },
+}
+
+impl ShotOutcome {
+    /// Returns whether a ship occupied the space.
+    pub fn is_hit(&self) -> bool {
+        *self != ShotOutcome::Miss
+    }
+
+    /// Returns whether the shot sunk a ship.
+    pub fn is_sunk(&self) -> bool {
+        matches!(self, ShotOutcome::Sunk { .. } | ShotOutcome::Won { .. })
+    }
+
+    /// Returns the index of the ship that occupied the space, if there was one.
+    pub fn ship_index(&self) -> Option<usize> {
+        match *self {
+            ShotOutcome::Miss => None,
+            ShotOutcome::Hit { ship_index }
+            | ShotOutcome::Sunk { ship_index, .. }
+            | ShotOutcome::Won { ship_index, .. } => Some(ship_index),
+        }
+    }
+}
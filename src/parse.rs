@@ -0,0 +1,71 @@
+//! Parsers for the game settings that can be chosen on the command line, shared by every binary
+//! that takes them.
+
+use rust_battleship::{AdjacencyRule, Difficulty, ShotRule};
+
+/// Parses a grid size written as the number of columns and rows, such as `12x12`.
+pub fn parse_grid(s: &str) -> Result<[u8; 2], String> {
+    let invalid = || {
+        format!(
+            "invalid grid size '{}', expected COLUMNSxROWS such as 10x10",
+            s
+        )
+    };
+    let (columns, rows) = s.split_once('x').ok_or_else(invalid)?;
+
+    match (columns.parse(), rows.parse()) {
+        (Ok(columns), Ok(rows)) => Ok([columns, rows]),
+        _ => Err(invalid()),
+    }
+}
+
+/// Parses a fleet written as comma-separated ship lengths, such as `5,4,3,3,2`.
+pub fn parse_fleet(s: &str) -> Result<Vec<u8>, String> {
+    s.split(',')
+        .map(|len| len.trim().parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| {
+            format!(
+                "invalid fleet '{}', expected ship lengths such as 5,4,3,3,2",
+                s
+            )
+        })
+}
+
+pub fn parse_difficulty(s: &str) -> Result<Difficulty, String> {
+    match s {
+        "easy" => Ok(Difficulty::Easy),
+        "normal" => Ok(Difficulty::Normal),
+        "hard" => Ok(Difficulty::Hard),
+        other => Err(format!(
+            "unknown difficulty '{}', expected easy, normal or hard",
+            other
+        )),
+    }
+}
+
+pub fn parse_shots(s: &str) -> Result<ShotRule, String> {
+    match s {
+        "single" => Ok(ShotRule::Single),
+        "salvo" => Ok(ShotRule::Salvo),
+        other => match other.parse() {
+            Ok(shots) if shots > 0 => Ok(ShotRule::Fixed(shots)),
+            _ => Err(format!(
+                "unknown shot rule '{}', expected single, salvo or a number",
+                other
+            )),
+        },
+    }
+}
+
+pub fn parse_adjacency(s: &str) -> Result<AdjacencyRule, String> {
+    match s {
+        "allowed" => Ok(AdjacencyRule::Allowed),
+        "no-orthogonal" => Ok(AdjacencyRule::NoOrthogonalTouch),
+        "no-touch" => Ok(AdjacencyRule::NoTouchIncludingDiagonals),
+        other => Err(format!(
+            "unknown adjacency rule '{}', expected allowed, no-orthogonal or no-touch",
+            other
+        )),
+    }
+}
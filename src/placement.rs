@@ -0,0 +1,13 @@
+/// Options for placing a whole fleet at once with [`Player::auto_place_fleet`].
+///
+/// [`Player::auto_place_fleet`]: crate::Player::auto_place_fleet
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PlacementOptions {
+    /// Whether ships must not be placed in spaces next to each other, even diagonally, whatever
+    /// the player's adjacency rule allows.
+    pub no_touching: bool,
+    /// Whether ships are more likely to be placed along the edges of the grid.
+    pub prefer_edges: bool,
+    /// Whether ships are more likely to be placed far away from the ships already placed.
+    pub spread_out: bool,
+}
@@ -1,46 +1,115 @@
-use crate::{direction::Direction, ship::Ship, space::Space};
+use crate::{
+    direction::Direction,
+    error::BattleshipError,
+    outcome::ShotOutcome,
+    placement::PlacementOptions,
+    settings::{search_fleet, AdjacencyRule},
+    ship::Ship,
+    space::Space,
+    target::TargetView,
+};
+use rand::{seq::SliceRandom, RngCore};
 use std::cmp;
 
+/// The number of times [`Player::auto_place_fleet`] starts a fleet again after running out of
+/// room for a ship, before giving up.
+const MAX_AUTO_PLACEMENT_ATTEMPTS: usize = 100;
+
+/// A player's grid, ships and grid cursor.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Player {
     is_cpu: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    is_remote: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    adjacency: AdjacencyRule,
+    #[cfg_attr(feature = "serde", serde(default))]
+    mark_around_sunk: bool,
     spaces: Vec<Space>,
     ships: Vec<Ship>,
+    fleet_size: usize,
     grid_size: [u8; 2],
     grid_cursor: [u8; 2],
+    #[cfg_attr(feature = "serde", serde(skip))]
+    placement_undo: Vec<Vec<Ship>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    placement_redo: Vec<Vec<Ship>>,
 }
 
 impl Player {
+    /// Creates a new player with an unchecked grid of the given size and room for `ship_count`
+    /// ships, which may be placed next to each other until an adjacency rule is set.
     pub fn new(grid_size: [u8; 2], ship_count: usize, is_cpu: bool) -> Player {
         Player {
-            is_cpu: is_cpu,
+            is_cpu,
+            is_remote: false,
+            adjacency: AdjacencyRule::Allowed,
+            mark_around_sunk: false,
             spaces: Space::all_grid_spaces(&grid_size),
             ships: Vec::with_capacity(ship_count),
-            grid_size: grid_size,
+            fleet_size: ship_count,
+            grid_size,
             grid_cursor: [0, 0],
+            placement_undo: Vec::new(),
+            placement_redo: Vec::new(),
+        }
+    }
+
+    /// Creates a new player with an unchecked grid of the given size, who is controlled by another
+    /// instance of the game and whose ships are only known to that instance.
+    pub fn new_remote(grid_size: [u8; 2], ship_count: usize) -> Player {
+        Player {
+            is_remote: true,
+            ..Player::new(grid_size, ship_count, false)
         }
     }
 
-    /// Selects a space.
+    /// Selects a space, and returns the index of the ship that was hit, if any.
     ///
     /// # Errors
     ///
-    /// Returns an error if the space at `pos` was already checked.
-    pub fn select_space(&mut self, pos: &[u8; 2]) -> Result<(), &'static str> {
+    /// Returns an error if no space exists at `pos`, or if the space at `pos` was already checked.
+    pub fn select_space(&mut self, pos: &[u8; 2]) -> Result<Option<usize>, BattleshipError> {
+        if !self.valid_space(pos) {
+            return Err(BattleshipError::OutOfBounds);
+        }
+
         let space_index = self.space_index(pos);
         let ship_hit = self.ships.iter().position(|s| s.pos().contains(pos));
         self.spaces[space_index].set_checked(ship_hit.is_some())?;
 
-        Ok(())
+        Ok(ship_hit)
+    }
+
+    /// Sets the space at `pos` as checked, and whether it was hit, without considering the
+    /// player's ships.
+    ///
+    /// This is used to record shots at a remote player, whose ships are unknown.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no space exists at `pos`, or if the space at `pos` was already checked.
+    pub fn set_space_checked(&mut self, pos: &[u8; 2], hit: bool) -> Result<(), BattleshipError> {
+        if !self.valid_space(pos) {
+            return Err(BattleshipError::OutOfBounds);
+        }
+
+        let space_index = self.space_index(pos);
+        self.spaces[space_index].set_checked(hit)
     }
 
     /// Sets the ship at the given position as sunk if all spaces it occupies
     /// have been checked, and returns whether the ship was sunk.
     ///
+    /// If the player marks the spaces around sunk ships, the unchecked spaces that the adjacency
+    /// rule keeps free around the ship are set as known to be empty.
+    ///
     /// # Errors
     ///
     /// Returns an error if no ship is at the given position, or if the ship
     /// state is not active.
-    pub fn sink_ship_if_all_hit(&mut self, pos: &[u8; 2]) -> Result<bool, &'static str> {
+    pub fn sink_ship_if_all_hit(&mut self, pos: &[u8; 2]) -> Result<bool, BattleshipError> {
         if let Some(index) = self.ships.iter().position(|s| s.pos().contains(pos)) {
             let sunk = self.ships[index]
                 .pos()
@@ -49,100 +118,325 @@ impl Player {
 
             if sunk {
                 self.ships[index].set_sunk()?;
+
+                if self.mark_around_sunk {
+                    self.mark_around_ship(index)?;
+                }
             }
 
             Ok(sunk)
         } else {
-            Err("no ship at the given position")
+            Err(BattleshipError::NoShipAtPosition)
         }
     }
 
-    /// Returns whether all of the player's ships have been sunk.
-    pub fn all_ships_sunk(&self) -> bool {
-        self.ships.iter().all(|ship| ship.is_sunk())
+    /// Sets whether the spaces around each of the player's ships are marked as known to be empty
+    /// when it's sunk, where the adjacency rule means they can't contain another ship.
+    pub fn set_mark_around_sunk(&mut self, mark: bool) {
+        self.mark_around_sunk = mark;
     }
 
-    /// Returns suggestions for the best spaces to check based on the player's hit spaces and ships.
+    /// Sets the unchecked spaces next to the ship at `index` as known to be empty, including
+    /// diagonally if the adjacency rule doesn't allow ships to touch diagonally.
     ///
-    /// This is intended for use in cases where the active player is computer-controlled, to
-    /// determine the space they check.  However, it could also be used to suggest a space that a
-    /// human player could check.
-    pub fn suggested_checks(&self) -> Vec<[u8; 2]> {
-        let mut select = vec![];
-        let directions = Direction::all();
-        let hit_spaces = self
-            .spaces
+    /// # Errors
+    ///
+    /// Returns an error if a space could not be set as known to be empty.
+    fn mark_around_ship(&mut self, index: usize) -> Result<(), BattleshipError> {
+        let diagonals = match self.adjacency {
+            AdjacencyRule::Allowed => return Ok(()),
+            AdjacencyRule::NoOrthogonalTouch => false,
+            AdjacencyRule::NoTouchIncludingDiagonals => true,
+        };
+        let around = self.ships[index]
+            .pos()
             .iter()
-            .filter(|s| s.is_hit() && self.ship(s.pos()).unwrap().is_active())
-            .collect::<Vec<&Space>>();
+            .flat_map(|pos| self.neighbours(pos, diagonals))
+            .collect::<Vec<[u8; 2]>>();
 
-        // Check for a line of hit spaces.
-        for space in &hit_spaces {
-            for direction in &directions {
-                let unchecked = self.find_unchecked_space(space.pos(), *direction, true);
+        for pos in around {
+            let space_index = self.space_index(&pos);
 
-                if let Some(pos) = unchecked {
-                    if !select.contains(&pos) {
-                        select.push(pos);
-                    }
-                }
+            if self.spaces[space_index].is_unchecked() {
+                self.spaces[space_index].set_known_empty()?;
             }
         }
 
-        // If a hit space was found, but no hit spaces next to it, look for unchecked spaces next
-        // to it.
-        if hit_spaces.len() > 0 && select.is_empty() {
-            for direction in &directions {
-                let unchecked = self.find_unchecked_space(hit_spaces[0].pos(), *direction, false);
+        Ok(())
+    }
 
-                if let Some(pos) = unchecked {
-                    select.push(pos);
-                }
+    /// Returns whether all of the player's ships have been sunk.
+    pub fn all_ships_sunk(&self) -> bool {
+        self.ships.iter().all(|ship| ship.is_sunk())
+    }
+
+    /// Returns what an opponent knows about the player's grid from the shots they've taken.
+    ///
+    /// A remote player's ships aren't known, so none of their hit spaces are known to belong to
+    /// a ship; [`Game::target_view`] also knows about them from the shots' outcomes.
+    ///
+    /// [`Game::target_view`]: crate::Game::target_view
+    pub fn target_view(&self) -> TargetView {
+        let lengths = self.ships.iter().map(|ship| ship.len() as u8).collect();
+        let mut view = TargetView::from_spaces(self.grid_size, self.spaces.clone(), lengths);
+
+        for (ship_index, ship) in self.ships.iter().enumerate() {
+            let outcome = match ship.is_sunk() {
+                true => ShotOutcome::Sunk {
+                    ship_index,
+                    length: ship.len(),
+                },
+                false => ShotOutcome::Hit { ship_index },
+            };
+
+            for pos in ship.pos().iter().filter(|p| self.space(p).is_hit()) {
+                view.record_outcome(pos, outcome);
             }
         }
 
-        // If no candidates have been found yet, just add any unchecked space.
-        // TODO: smarter checking for spaces that remaining ships could realistically occupy.
-        if select.is_empty() {
-            select = self
-                .spaces
-                .iter()
-                .filter(|space| space.is_unchecked())
-                .map(|space| *space.pos())
-                .collect::<Vec<[u8; 2]>>();
-        }
+        view
+    }
+
+    /// Returns suggestions for the best spaces to check based on the player's hit spaces and
+    /// sunk ships.  See [`TargetView::suggested_checks`].
+    pub fn suggested_checks(&self) -> Vec<[u8; 2]> {
+        self.target_view().suggested_checks()
+    }
+
+    /// Returns suggestions for the best spaces to check based on where the player's remaining
+    /// ships could be positioned.  See [`TargetView::density_checks`].
+    pub fn density_checks(&self) -> Vec<[u8; 2]> {
+        self.target_view().density_checks()
+    }
+
+    /// Returns the positions of all unchecked spaces.
+    pub fn unchecked_spaces(&self) -> Vec<[u8; 2]> {
+        self.spaces
+            .iter()
+            .filter(|space| space.is_unchecked())
+            .map(|space| *space.pos())
+            .collect()
+    }
 
-        select
+    /// Returns, for each space, the number of ways the player's remaining ships could be
+    /// positioned to occupy it, in the same order as `spaces()`.  See
+    /// [`TargetView::placement_density`].
+    pub fn placement_density(&self) -> Vec<u32> {
+        self.target_view().placement_density()
     }
 
+    /// Adds a ship with the given head position, direction and length.
+    ///
+    /// If `placement` is true, the ship is added in the placement state and may overlap other
+    /// ships; otherwise it is added as an active ship.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player already has all of their ships, if the ship would be
+    /// partially out of bounds, or if a non-placement ship would be in an invalid position.
     pub fn add_ship(
         &mut self,
         head: [u8; 2],
         direction: Direction,
         length: u8,
         placement: bool,
-    ) -> Result<(), &'static str> {
-        if self.ships.len() == self.ships.capacity() {
-            Err("tried to add ship to a player with all ships already added")
+    ) -> Result<(), BattleshipError> {
+        if self.ships.len() == self.fleet_size {
+            Err(BattleshipError::AllShipsAdded)
         } else {
             let pos = self
                 .get_ship_position(head, direction, length)
-                .ok_or("tried to place a ship partially out of bounds")?;
+                .ok_or(BattleshipError::OutOfBounds)?;
+
+            if !placement {
+                self.check_ship_position(&pos)?;
+            }
+
+            let mut ship = Ship::new(pos)?;
+
+            if !placement {
+                ship.set_active()?;
+            }
 
-            if !placement && !self.valid_ship_position(&pos) {
-                Err("tried to place a ship in an invalid position")
-            } else {
+            self.ships.push(ship);
+
+            Ok(())
+        }
+    }
+
+    /// Replaces all of the player's ships with a randomly placed fleet of ships with the given
+    /// lengths, in the same order, placed according to `options`.
+    ///
+    /// The ships are placed one at a time, each in a random position chosen from every position
+    /// that's still valid, weighted by `options`.  Replacing the fleet can be undone like any
+    /// other placement.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player doesn't have room for `ships`, if any of `ships` has a
+    /// length of zero, or if a fleet couldn't be placed after several attempts, in which case the
+    /// player's ships are left unchanged.
+    pub fn auto_place_fleet(
+        &mut self,
+        ships: &[u8],
+        options: PlacementOptions,
+        rng: &mut dyn RngCore,
+    ) -> Result<(), BattleshipError> {
+        if ships.len() > self.fleet_size {
+            return Err(BattleshipError::AllShipsAdded);
+        }
+
+        let previous = self.replace_ships(Vec::new());
+
+        for _ in 0..MAX_AUTO_PLACEMENT_ATTEMPTS {
+            match self.try_auto_place_fleet(ships, options, rng) {
+                Ok(true) => {
+                    self.push_placement_undo(previous);
+
+                    return Ok(());
+                }
+                // The ships placed so far have left no room for the rest; start again.
+                Ok(false) => self.ships.clear(),
+                Err(err) => {
+                    self.replace_ships(previous);
+
+                    return Err(err);
+                }
+            }
+        }
+
+        self.replace_ships(previous);
+
+        Err(BattleshipError::FleetDoesNotFit)
+    }
+
+    /// Adds active ships with the given lengths in random positions, and returns whether there
+    /// was room for all of them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a ship could not be created, such as one with a length of zero.
+    fn try_auto_place_fleet(
+        &mut self,
+        ships: &[u8],
+        options: PlacementOptions,
+        rng: &mut dyn RngCore,
+    ) -> Result<bool, BattleshipError> {
+        for &length in ships {
+            if length == 0 {
+                return Err(BattleshipError::InvalidShipShape);
+            }
+
+            let candidates = self.placement_candidates(length, options);
+
+            let Ok((pos, _)) = candidates.choose_weighted(rng, |(_, weight)| *weight) else {
+                return Ok(false);
+            };
+
+            let mut ship = Ship::new(pos.clone())?;
+            ship.set_active()?;
+            self.ships.push(ship);
+        }
+
+        Ok(true)
+    }
+
+    /// Replaces all of the player's ships with a fleet of ships with the given lengths, in the
+    /// same order, placed by searching every position that follows the player's adjacency rule.
+    ///
+    /// Unlike [`Player::auto_place_fleet`], this finds a placement for any fleet that
+    /// [`GameSettings::validate`](crate::GameSettings::validate) accepts, but always the same
+    /// one.  Replacing the fleet can be undone like any other placement.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player doesn't have room for `ships`, if any of `ships` has a
+    /// length of zero, or if no placement was found, in which case the player's ships are left
+    /// unchanged.
+    pub(crate) fn search_place_fleet(&mut self, ships: &[u8]) -> Result<(), BattleshipError> {
+        if ships.len() > self.fleet_size {
+            return Err(BattleshipError::AllShipsAdded);
+        } else if ships.contains(&0) {
+            return Err(BattleshipError::InvalidShipShape);
+        }
+
+        let fleet = search_fleet(self.grid_size, ships, self.adjacency)
+            .ok_or(BattleshipError::FleetDoesNotFit)?;
+        let ships = fleet
+            .into_iter()
+            .map(|pos| {
                 let mut ship = Ship::new(pos)?;
+                ship.set_active()?;
+
+                Ok(ship)
+            })
+            .collect::<Result<Vec<Ship>, BattleshipError>>()?;
+        let previous = self.replace_ships(ships);
+        self.push_placement_undo(previous);
 
-                if !placement {
-                    ship.set_active()?;
+        Ok(())
+    }
+
+    /// Returns every valid position for a new ship of the given length, with the weight given
+    /// to it by `options`.
+    fn placement_candidates(
+        &self,
+        length: u8,
+        options: PlacementOptions,
+    ) -> Vec<(Vec<[u8; 2]>, u32)> {
+        let mut candidates = vec![];
+
+        for space in &self.spaces {
+            for direction in Direction::all() {
+                let Some(pos) = self.get_ship_position(*space.pos(), direction, length) else {
+                    continue;
+                };
+
+                if pos.iter().any(|p| {
+                    self.ship_is_in_space(p)
+                        || self.breaks_adjacency_rule(p)
+                        || options.no_touching && self.ship_is_next_to(p, true)
+                }) {
+                    continue;
                 }
 
-                self.ships.push(ship);
+                let mut weight = 1;
+
+                if options.prefer_edges {
+                    weight += 4 * pos.iter().filter(|p| self.is_edge(p)).count() as u32;
+                }
 
-                Ok(())
+                if options.spread_out {
+                    weight *= 1 + self.distance_to_ships(&pos);
+                }
+
+                candidates.push((pos, weight));
             }
         }
+
+        candidates
+    }
+
+    /// Returns whether the given position is on the edge of the grid.
+    fn is_edge(&self, pos: &[u8; 2]) -> bool {
+        pos[0] == 0
+            || pos[1] == 0
+            || pos[0] == self.grid_size[0] - 1
+            || pos[1] == self.grid_size[1] - 1
+    }
+
+    /// Returns the shortest distance, in spaces along the grid, between any of the given
+    /// positions and any of the player's ships, or zero if the player has no ships.
+    fn distance_to_ships(&self, pos: &[[u8; 2]]) -> u32 {
+        self.ships
+            .iter()
+            .flat_map(|ship| ship.pos())
+            .flat_map(|a| {
+                pos.iter()
+                    .map(move |b| a[0].abs_diff(b[0]) as u32 + a[1].abs_diff(b[1]) as u32)
+            })
+            .min()
+            .unwrap_or(0)
     }
 
     /// Moves the player's placement ship in the given direction.
@@ -151,51 +445,137 @@ impl Player {
     ///
     /// Returns an error if the player does not have a placement ship, or if the ship could not be
     /// moved in `direction` without going out of bounds.
-    pub fn move_placement_ship(&mut self, direction: Direction) -> Result<(), &'static str> {
-        let index = self.ships.len() - 1;
-        let old_head = self.ships[index].pos()[0];
+    pub fn move_placement_ship(&mut self, direction: Direction) -> Result<(), BattleshipError> {
+        let ship = self.placement_ship()?;
+        let old_head = ship.pos()[0];
         let new_head = self
             .movement(&old_head, direction)
-            .ok_or("movement not possible without going out of bounds")?;
+            .ok_or(BattleshipError::OutOfBounds)?;
         let ship_pos = self
-            .get_ship_position(
-                new_head,
-                self.ships[index].dir(),
-                self.ships[index].len() as u8,
-            )
-            .ok_or("movement not possible without going out of bounds")?;
+            .get_ship_position(new_head, ship.dir(), ship.len() as u8)
+            .ok_or(BattleshipError::OutOfBounds)?;
 
-        self.ships[index].set_pos(ship_pos)?;
+        self.placement_ship_mut()?.set_pos(ship_pos)?;
 
         Ok(())
     }
 
-    pub fn place_placement_ship(&mut self) -> Result<(), &'static str> {
-        let index = self.ships.len() - 1;
+    /// Places the player's placement ship, setting it as active.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player has no placement ship, or if the placement ship is in an
+    /// invalid position.
+    pub fn place_placement_ship(&mut self) -> Result<(), BattleshipError> {
+        let ships = self.ships.clone();
+        self.check_ship_position(self.placement_ship()?.pos())?;
+        self.placement_ship_mut()?.set_active()?;
+        self.push_placement_undo(ships);
 
-        // Ensure the ship doesn't overlap with another ship.
-        if !self.valid_ship_position(&self.ships[index].pos()) {
-            Err("placement ship overlaps with another ship")
-        } else {
-            self.ships[index].set_active()?;
+        Ok(())
+    }
 
-            Ok(())
+    /// Lifts the placed ship at `pos`, returning it to the placement state so that it can be
+    /// moved and placed again.
+    ///
+    /// A placement ship that hasn't been placed yet is removed, so that the lifted ship is the
+    /// only placement ship.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no placed ship is at `pos`, or if another lifted ship hasn't been
+    /// placed again.
+    pub fn lift_ship(&mut self, pos: &[u8; 2]) -> Result<(), BattleshipError> {
+        let index = self
+            .ships
+            .iter()
+            .position(|s| s.is_active() && s.pos().contains(pos))
+            .ok_or(BattleshipError::NoShipAtPosition)?;
+        let placement = self.ships.iter().position(|s| s.is_placement());
+
+        if placement.is_some_and(|i| i != self.ships.len() - 1) {
+            return Err(BattleshipError::ShipAlreadyLifted);
+        }
+
+        let ships = self.ships.clone();
+
+        if placement.is_some() {
+            self.ships.pop();
         }
+
+        self.ships[index].set_placement()?;
+        self.push_placement_undo(ships);
+
+        Ok(())
+    }
+
+    /// Undoes the player's last ship placement or lift, returning their ships to how they were
+    /// before it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no placement or lift to undo.
+    pub fn undo_placement(&mut self) -> Result<(), BattleshipError> {
+        let ships = self
+            .placement_undo
+            .pop()
+            .ok_or(BattleshipError::NothingToUndo)?;
+        let undone = self.replace_ships(ships);
+        self.placement_redo.push(undone);
+
+        Ok(())
+    }
+
+    /// Redoes the player's last undone ship placement or lift.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if nothing has been undone since the last placement or lift.
+    pub fn redo_placement(&mut self) -> Result<(), BattleshipError> {
+        let ships = self
+            .placement_redo
+            .pop()
+            .ok_or(BattleshipError::NothingToRedo)?;
+        let redone = self.replace_ships(ships);
+        self.placement_undo.push(redone);
+
+        Ok(())
+    }
+
+    /// Records `ships` as the player's ships before a placement or lift, so that it can be
+    /// undone, and forgets any undone placements.
+    fn push_placement_undo(&mut self, ships: Vec<Ship>) {
+        self.placement_undo.push(ships);
+        self.placement_redo.clear();
+    }
+
+    /// Replaces the player's ships with `ships`, and returns the previous ships.
+    fn replace_ships(&mut self, ships: Vec<Ship>) -> Vec<Ship> {
+        std::mem::replace(&mut self.ships, ships)
     }
 
     /// Rotates the player's placement ship during the ship placement game state.
     ///
     /// # Errors
     ///
-    /// Returns an error if the player does not have a placement ship.
-    pub fn rotate_placement_ship(&mut self) -> Result<(), &'static str> {
-        let index = self.ships.len() - 1;
-        let ship_len = self.ships[index].len() as u8;
-        let dir = self.ships[index].dir().rotated();
+    /// Returns an error if the player does not have a placement ship, or if the ship is too long
+    /// to fit on the grid after rotating.
+    pub fn rotate_placement_ship(&mut self) -> Result<(), BattleshipError> {
+        let ship = self.placement_ship()?;
+        let ship_len = ship.len() as u8;
+        let dir = ship.dir().rotated();
+        let grid_len = match dir {
+            Direction::North | Direction::South => self.grid_size[1],
+            Direction::East | Direction::West => self.grid_size[0],
+        };
+
+        if ship_len > grid_len {
+            return Err(BattleshipError::OutOfBounds);
+        }
 
         // If the current ship position would cause the rotation to position the ship partially out
         // of bounds, adjust the position such that the ship will be entirely within bounds.
-        let old_head = self.ships[index].pos()[0];
+        let old_head = ship.pos()[0];
         let new_head = match dir {
             Direction::North => [
                 old_head[0],
@@ -211,7 +591,7 @@ impl Player {
 
         // get_ship_position() should always return Some(ship_pos) in this situation.
         let ship_pos = self.get_ship_position(new_head, dir, ship_len).unwrap();
-        self.ships[index].set_pos(ship_pos)?;
+        self.placement_ship_mut()?.set_pos(ship_pos)?;
 
         Ok(())
     }
@@ -228,43 +608,48 @@ impl Player {
         direction: Direction,
         length: u8,
     ) -> Option<Vec<[u8; 2]>> {
-        let valid = match direction {
-            Direction::North => head[1] + length <= self.grid_size[1],
-            Direction::East => head[0] >= length - 1,
-            Direction::South => head[1] >= length - 1,
-            Direction::West => head[0] + length <= self.grid_size[0],
-        };
-
-        if valid {
-            let mut ship = Vec::with_capacity(length as usize);
-
-            for pos in 0..length {
-                let pos_u8 = pos as u8;
-
-                ship.push(match direction {
-                    Direction::North => [head[0], head[1] + pos_u8],
-                    Direction::East => [head[0] - pos_u8, head[1]],
-                    Direction::South => [head[0], head[1] - pos_u8],
-                    Direction::West => [head[0] + pos_u8, head[1]],
-                });
-            }
+        ship_position(self.grid_size, head, direction, length)
+    }
 
-            Some(ship)
+    /// Checks that the given ship position is valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the position is out of bounds, overlaps with another ship, or is next
+    /// to another ship where the player's adjacency rule doesn't allow it.
+    fn check_ship_position(&self, new_ship: &[[u8; 2]]) -> Result<(), BattleshipError> {
+        if !new_ship.iter().all(|s| self.valid_space(s)) {
+            Err(BattleshipError::OutOfBounds)
+        } else if new_ship.iter().any(|s| self.ship_is_in_space(s)) {
+            Err(BattleshipError::Overlap)
+        } else if new_ship.iter().any(|s| self.breaks_adjacency_rule(s)) {
+            Err(BattleshipError::Adjacent {
+                rule: self.adjacency,
+            })
         } else {
-            None
+            Ok(())
         }
     }
 
-    /// Checks that the given ship position is valid.
-    ///
-    /// If the player is CPU-controlled, a ship in a space next to another ship
-    /// will be considered invalid.
-    fn valid_ship_position(&self, new_ship: &[[u8; 2]]) -> bool {
-        new_ship.iter().all(|s| {
-            self.valid_space(s)
-                && !self.ship_is_in_space(s)
-                && !(self.ship_is_next_to(s) && self.is_cpu)
-        })
+    /// Returns the rule for whether the player's ships may be placed next to each other.
+    pub fn adjacency_rule(&self) -> AdjacencyRule {
+        self.adjacency
+    }
+
+    /// Sets the rule for whether the player's ships may be placed next to each other, which
+    /// applies to ships placed from now on.
+    pub fn set_adjacency_rule(&mut self, rule: AdjacencyRule) {
+        self.adjacency = rule;
+    }
+
+    /// Returns whether a ship in the specified grid coordinates would be too close to another
+    /// ship under the player's adjacency rule.
+    fn breaks_adjacency_rule(&self, pos: &[u8; 2]) -> bool {
+        match self.adjacency {
+            AdjacencyRule::Allowed => false,
+            AdjacencyRule::NoOrthogonalTouch => self.ship_is_next_to(pos, false),
+            AdjacencyRule::NoTouchIncludingDiagonals => self.ship_is_next_to(pos, true),
+        }
     }
 
     /// Gets a reference to the ships.
@@ -272,11 +657,6 @@ impl Player {
         &self.ships
     }
 
-    /// Gets a reference to a ship if it is in the given position.
-    fn ship(&self, pos: &[u8; 2]) -> Option<&Ship> {
-        self.ships.iter().find(|s| s.pos().contains(pos))
-    }
-
     /// Returns whether a ship occupies the specified grid coordinates.
     pub fn ship_is_in_space(&self, pos: &[u8; 2]) -> bool {
         self.ships
@@ -284,18 +664,37 @@ impl Player {
             .any(|s| s.pos().contains(pos) && !s.is_placement())
     }
 
-    /// Returns whether there is a ship next to the specified grid coordinates.
-    fn ship_is_next_to(&self, pos: &[u8; 2]) -> bool {
-        let &[x, y] = pos;
+    /// Returns whether there is a ship next to the specified grid coordinates, including
+    /// diagonally if `diagonals` is true.
+    fn ship_is_next_to(&self, pos: &[u8; 2], diagonals: bool) -> bool {
+        self.neighbours(pos, diagonals)
+            .iter()
+            .any(|p| self.ship_is_in_space(p))
+    }
+
+    /// Returns the positions of the spaces next to the specified grid coordinates, including
+    /// diagonally if `diagonals` is true.
+    fn neighbours(&self, pos: &[u8; 2], diagonals: bool) -> Vec<[u8; 2]> {
+        let mut neighbours = Vec::with_capacity(8);
+
+        for dx in -1i16..=1 {
+            for dy in -1i16..=1 {
+                if (dx, dy) == (0, 0) || !diagonals && dx != 0 && dy != 0 {
+                    continue;
+                }
 
-        // Left
-        x > 0 && self.ship_is_in_space(&[x - 1, y])
-            // Right
-            || x < self.grid_size[0] - 1 && self.ship_is_in_space(&[x + 1, y])
-            // Above
-            || y > 0 && self.ship_is_in_space(&[x, y - 1])
-            // Below
-            || y < self.grid_size[1] - 1 && self.ship_is_in_space(&[x, y + 1])
+                let x = pos[0] as i16 + dx;
+                let y = pos[1] as i16 + dy;
+
+                if (0..self.grid_size[0] as i16).contains(&x)
+                    && (0..self.grid_size[1] as i16).contains(&y)
+                {
+                    neighbours.push([x as u8, y as u8]);
+                }
+            }
+        }
+
+        neighbours
     }
 
     /// Gets a reference to the spaces.
@@ -315,67 +714,13 @@ impl Player {
 
     /// Calculates the index of the given position in the spaces vector.
     fn space_index(&self, pos: &[u8; 2]) -> usize {
-        self.grid_size[0] as usize * pos[0] as usize + pos[1] as usize
+        self.grid_size[1] as usize * pos[0] as usize + pos[1] as usize
     }
 
     /// Returns the coordinates of a movement from `pos` in a `direction`.
     /// Returns `None` if the movement is not possible.
     fn movement(&self, pos: &[u8; 2], direction: Direction) -> Option<[u8; 2]> {
-        let valid = match direction {
-            Direction::North => pos[1] > 0,
-            Direction::East => pos[0] < self.grid_size[0] - 1,
-            Direction::South => pos[1] < self.grid_size[1] - 1,
-            Direction::West => pos[0] > 0,
-        };
-
-        match valid {
-            true => Some(match direction {
-                Direction::North => [pos[0], pos[1] - 1],
-                Direction::East => [pos[0] + 1, pos[1]],
-                Direction::South => [pos[0], pos[1] + 1],
-                Direction::West => [pos[0] - 1, pos[1]],
-            }),
-            false => None,
-        }
-    }
-
-    /// Finds the first non-hit, unchecked space in a `direction` from `pos`.
-    /// Can also make sure the space is at the end of a `line` of hit spaces.
-    /// Returns `None` if the first non-hit space has been checked or if a grid
-    /// boundary is reached.
-    fn find_unchecked_space(
-        &self,
-        pos: &[u8; 2],
-        direction: Direction,
-        check_for_line: bool,
-    ) -> Option<[u8; 2]> {
-        let mut check_pos = self.movement(pos, direction);
-
-        while let Some(next_pos) = check_pos {
-            let next_space = self.space(&next_pos);
-
-            match next_space.is_hit() {
-                true => check_pos = self.movement(&next_pos, direction),
-                false => {
-                    if !next_space.is_unchecked() {
-                        check_pos = None;
-                    }
-                    break;
-                }
-            };
-        }
-
-        if check_for_line && check_pos.is_some() {
-            let unchecked = check_pos.unwrap();
-            let opposite_dir = direction.opposite();
-            let prev_pos = self.movement(&unchecked, opposite_dir).unwrap();
-
-            if &prev_pos == pos {
-                check_pos = None;
-            }
-        }
-
-        check_pos
+        movement(self.grid_size, pos, direction)
     }
 
     /// Returns the coordinates of the player's grid cursor.
@@ -388,13 +733,13 @@ impl Player {
     /// # Errors
     ///
     /// Returns an error if moving the grid cursor in `direction` would move it out of bounds.
-    pub fn move_grid_cursor(&mut self, direction: Direction) -> Result<(), &'static str> {
+    pub fn move_grid_cursor(&mut self, direction: Direction) -> Result<(), BattleshipError> {
         if let Some(new_cursor) = self.movement(&self.grid_cursor, direction) {
             self.set_grid_cursor(&new_cursor)?;
 
             Ok(())
         } else {
-            Err("tried to move grid cursor out of bounds")
+            Err(BattleshipError::OutOfBounds)
         }
     }
 
@@ -403,41 +748,335 @@ impl Player {
     /// # Errors
     ///
     /// Returns an error if no space exists at `pos`.
-    pub fn set_grid_cursor(&mut self, pos: &[u8; 2]) -> Result<(), &'static str> {
-        if self.space_index(pos) < self.spaces.len() {
+    pub fn set_grid_cursor(&mut self, pos: &[u8; 2]) -> Result<(), BattleshipError> {
+        if self.valid_space(pos) {
             self.grid_cursor = *pos;
 
             Ok(())
         } else {
-            Err("tried to set the grid cursor to a nonexistent space")
+            Err(BattleshipError::OutOfBounds)
         }
     }
 
+    /// Returns whether the player is computer-controlled.
     pub fn is_cpu(&self) -> bool {
         self.is_cpu
     }
 
-    pub fn placement_ship(&self) -> Result<&Ship, &'static str> {
-        let ships_len = self.ships.len();
+    /// Returns whether the player is controlled by another instance of the game.
+    pub fn is_remote(&self) -> bool {
+        self.is_remote
+    }
 
-        if ships_len == 0 {
-            Err("player has no ships")
-        } else if !self.ships[ships_len - 1].is_placement() {
-            Err("player has no placement ship")
-        } else {
-            Ok(&self.ships[self.ships.len() - 1])
+    /// Gets a reference to the player's placement ship.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player has no placement ship.
+    pub fn placement_ship(&self) -> Result<&Ship, BattleshipError> {
+        self.ships
+            .iter()
+            .find(|s| s.is_placement())
+            .ok_or(BattleshipError::NoPlacementShip)
+    }
+
+    /// Gets a mutable reference to the player's placement ship.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player has no placement ship.
+    pub fn placement_ship_mut(&mut self) -> Result<&mut Ship, BattleshipError> {
+        self.ships
+            .iter_mut()
+            .find(|s| s.is_placement())
+            .ok_or(BattleshipError::NoPlacementShip)
+    }
+}
+
+/// Returns a ship position on a grid of the given size, given its head position, direction and
+/// length, or `None` if it wouldn't be contained within the grid or its length is zero.
+pub(crate) fn ship_position(
+    grid_size: [u8; 2],
+    head: [u8; 2],
+    direction: Direction,
+    length: u8,
+) -> Option<Vec<[u8; 2]>> {
+    let valid = length > 0
+        && match direction {
+            Direction::North => head[1] as usize + length as usize <= grid_size[1] as usize,
+            Direction::East => head[0] as usize + 1 >= length as usize,
+            Direction::South => head[1] as usize + 1 >= length as usize,
+            Direction::West => head[0] as usize + length as usize <= grid_size[0] as usize,
+        };
+
+    if valid {
+        let mut ship = Vec::with_capacity(length as usize);
+
+        for pos in 0..length {
+            ship.push(match direction {
+                Direction::North => [head[0], head[1] + pos],
+                Direction::East => [head[0] - pos, head[1]],
+                Direction::South => [head[0], head[1] - pos],
+                Direction::West => [head[0] + pos, head[1]],
+            });
         }
+
+        Some(ship)
+    } else {
+        None
     }
+}
 
-    pub fn placement_ship_mut(&mut self) -> Result<&mut Ship, &'static str> {
-        let ships_len = self.ships.len();
+/// Returns the coordinates of a movement from `pos` in a `direction` on a grid of the given
+/// size, or `None` if the movement would leave the grid.
+pub(crate) fn movement(grid_size: [u8; 2], pos: &[u8; 2], direction: Direction) -> Option<[u8; 2]> {
+    let valid = match direction {
+        Direction::North => pos[1] > 0,
+        Direction::East => pos[0] < grid_size[0] - 1,
+        Direction::South => pos[1] < grid_size[1] - 1,
+        Direction::West => pos[0] > 0,
+    };
 
-        if ships_len == 0 {
-            Err("player has no ships")
-        } else if !self.ships[ships_len - 1].is_placement() {
-            Err("player has no placement ship")
-        } else {
-            Ok(&mut self.ships[ships_len - 1])
+    match valid {
+        true => Some(match direction {
+            Direction::North => [pos[0], pos[1] - 1],
+            Direction::East => [pos[0] + 1, pos[1]],
+            Direction::South => [pos[0], pos[1] + 1],
+            Direction::West => [pos[0] - 1, pos[1]],
+        }),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn placement_density() {
+        let mut player = Player::new([3, 3], 1, true);
+        assert!(player.add_ship([0, 0], Direction::West, 3, false).is_ok());
+        assert_eq!(player.placement_density(), vec![2; 9]);
+
+        assert_eq!(player.select_space(&[1, 1]), Ok(None));
+        assert_eq!(player.placement_density(), vec![2, 1, 2, 1, 0, 1, 2, 1, 2]);
+
+        assert_eq!(player.select_space(&[0, 0]), Ok(Some(0)));
+        assert_eq!(player.placement_density(), vec![0, 1, 2, 1, 0, 1, 2, 1, 2]);
+
+        assert_eq!(player.select_space(&[1, 0]), Ok(Some(0)));
+        assert_eq!(player.select_space(&[2, 0]), Ok(Some(0)));
+        assert_eq!(player.sink_ship_if_all_hit(&[2, 0]), Ok(true));
+        assert_eq!(player.placement_density(), vec![0; 9]);
+    }
+
+    #[test]
+    fn non_square_grid() {
+        let mut player = Player::new([3, 5], 3, false);
+        assert_eq!(player.spaces().len(), 15);
+        assert!(player.add_ship([2, 0], Direction::North, 5, false).is_ok());
+        assert!(player.add_ship([0, 4], Direction::West, 2, false).is_ok());
+        assert_eq!(
+            player.add_ship([0, 0], Direction::West, 4, false),
+            Err(BattleshipError::OutOfBounds)
+        );
+
+        for space in player.spaces() {
+            assert_eq!(player.space(space.pos()).pos(), space.pos());
         }
+
+        assert_eq!(player.select_space(&[2, 4]), Ok(Some(0)));
+        assert_eq!(player.select_space(&[1, 4]), Ok(Some(1)));
+        assert_eq!(player.select_space(&[1, 3]), Ok(None));
+        assert!(player.space(&[2, 4]).is_hit());
+        assert!(player.space(&[1, 3]).is_empty());
+        assert!(player.space(&[0, 4]).is_unchecked());
+        assert_eq!(player.placement_density().len(), 15);
+
+        assert!(player.set_grid_cursor(&[2, 4]).is_ok());
+        assert_eq!(
+            player.move_grid_cursor(Direction::East),
+            Err(BattleshipError::OutOfBounds)
+        );
+        assert_eq!(
+            player.set_grid_cursor(&[3, 0]),
+            Err(BattleshipError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn lift_ship() {
+        let mut player = Player::new([4, 4], 2, false);
+        assert!(player.add_ship([0, 0], Direction::West, 2, true).is_ok());
+        assert!(player.place_placement_ship().is_ok());
+        assert_eq!(
+            player.lift_ship(&[3, 3]),
+            Err(BattleshipError::NoShipAtPosition)
+        );
+        assert!(player.lift_ship(&[1, 0]).is_ok());
+        assert_eq!(player.placement_ship().unwrap().pos(), &[[0, 0], [1, 0]]);
+
+        assert!(player.undo_placement().is_ok());
+        assert!(player.placement_ship().is_err());
+        assert!(player.undo_placement().is_ok());
+        assert_eq!(player.undo_placement(), Err(BattleshipError::NothingToUndo));
+        assert!(player.redo_placement().is_ok());
+
+        // The restored ships still leave room for the rest of the fleet.
+        assert!(player.add_ship([0, 2], Direction::West, 3, true).is_ok());
+        assert!(player.place_placement_ship().is_ok());
+        assert_eq!(player.redo_placement(), Err(BattleshipError::NothingToRedo));
+        assert!(player.lift_ship(&[0, 0]).is_ok());
+        assert!(player
+            .placement_ship_mut()
+            .unwrap()
+            .set_pos(vec![[3, 0], [3, 1]])
+            .is_ok());
+        assert!(player.place_placement_ship().is_ok());
+        assert!(player.ship_is_in_space(&[3, 1]));
+        assert!(!player.ship_is_in_space(&[0, 0]));
+    }
+
+    #[test]
+    fn auto_place_fleet() {
+        let ships = [5, 4, 3, 3, 2];
+        let mut rng = StdRng::seed_from_u64(0);
+        let options = [
+            PlacementOptions::default(),
+            PlacementOptions {
+                no_touching: true,
+                ..PlacementOptions::default()
+            },
+            PlacementOptions {
+                no_touching: true,
+                prefer_edges: true,
+                spread_out: true,
+            },
+        ];
+
+        for options in options {
+            let mut player = Player::new([8, 8], ships.len(), false);
+            assert!(player.add_ship([0, 0], Direction::West, 5, true).is_ok());
+            assert!(player.auto_place_fleet(&ships, options, &mut rng).is_ok());
+
+            let lengths = player.ships().iter().map(|s| s.len() as u8);
+            assert!(lengths.eq(ships));
+            assert!(player.ships().iter().all(|s| s.is_active()));
+
+            for (i, ship) in player.ships().iter().enumerate() {
+                for pos in ship.pos() {
+                    let others = player.ships().iter().enumerate().filter(|(j, _)| *j != i);
+                    assert!(!others.clone().any(|(_, s)| s.pos().contains(pos)));
+
+                    if options.no_touching {
+                        let touching = Direction::all()
+                            .into_iter()
+                            .filter_map(|dir| player.movement(pos, dir))
+                            .any(|p| others.clone().any(|(_, s)| s.pos().contains(&p)));
+                        assert!(!touching);
+                    }
+                }
+            }
+
+            // Replacing the fleet can be undone, back to the single placement ship.
+            assert!(player.undo_placement().is_ok());
+            assert_eq!(player.ships().len(), 1);
+            assert!(player.placement_ship().is_ok());
+        }
+
+        let mut player = Player::new([3, 3], 3, false);
+        let options = PlacementOptions {
+            no_touching: true,
+            ..PlacementOptions::default()
+        };
+        assert_eq!(
+            player.auto_place_fleet(&[3, 3, 3], options, &mut rng),
+            Err(BattleshipError::FleetDoesNotFit)
+        );
+        assert!(player.ships().is_empty());
+        assert_eq!(
+            player.auto_place_fleet(&[1, 1, 1, 1], options, &mut rng),
+            Err(BattleshipError::AllShipsAdded)
+        );
+
+        // A ship that can't be created leaves the ships that were already placed.
+        assert!(player.add_ship([0, 0], Direction::West, 3, false).is_ok());
+        assert_eq!(
+            player.auto_place_fleet(&[1, 0], options, &mut rng),
+            Err(BattleshipError::InvalidShipShape)
+        );
+        assert_eq!(player.ships().len(), 1);
+        assert_eq!(player.ships()[0].pos(), &[[0, 0], [1, 0], [2, 0]]);
+        assert_eq!(player.undo_placement(), Err(BattleshipError::NothingToUndo));
+        assert_eq!(
+            player.add_ship([2, 2], Direction::East, 0, true),
+            Err(BattleshipError::OutOfBounds)
+        );
+        assert_eq!(ship_position([3, 3], [0, 0], Direction::East, 0), None);
+        assert_eq!(ship_position([3, 3], [0, 0], Direction::South, 0), None);
+    }
+
+    #[test]
+    fn mark_around_sunk() {
+        for (adjacency, marked) in [
+            (AdjacencyRule::Allowed, 0),
+            (AdjacencyRule::NoOrthogonalTouch, 2),
+            (AdjacencyRule::NoTouchIncludingDiagonals, 3),
+        ] {
+            let mut player = Player::new([4, 4], 2, false);
+            player.set_adjacency_rule(adjacency);
+            player.set_mark_around_sunk(true);
+            assert!(player.add_ship([0, 0], Direction::West, 2, false).is_ok());
+            assert!(player.add_ship([3, 2], Direction::North, 2, false).is_ok());
+            assert_eq!(player.select_space(&[1, 1]), Ok(None));
+
+            for pos in [[0, 0], [1, 0]] {
+                assert_eq!(player.select_space(&pos), Ok(Some(0)));
+            }
+
+            assert_eq!(player.sink_ship_if_all_hit(&[1, 0]), Ok(true));
+
+            let known_empty = player.spaces().iter().filter(|s| s.is_known_empty());
+            assert_eq!(known_empty.count(), marked);
+            assert!(player.space(&[1, 1]).is_empty());
+
+            // Known empty spaces are never suggested.
+            for pos in player.suggested_checks() {
+                assert!(player.space(&pos).is_unchecked());
+            }
+
+            assert_eq!(
+                player.select_space(&[0, 1]).is_err(),
+                adjacency != AdjacencyRule::Allowed
+            );
+        }
+    }
+
+    #[test]
+    fn suggested_checks() {
+        let mut player = Player::new([3, 3], 1, true);
+        assert!(player.add_ship([0, 0], Direction::West, 3, false).is_ok());
+        assert_eq!(player.select_space(&[1, 1]), Ok(None));
+        assert_eq!(
+            player.suggested_checks(),
+            vec![[0, 0], [0, 2], [2, 0], [2, 2]]
+        );
+    }
+
+    #[test]
+    fn density_checks() {
+        let mut player = Player::new([3, 3], 1, true);
+        assert!(player.add_ship([0, 0], Direction::West, 3, false).is_ok());
+        assert_eq!(player.density_checks(), vec![[0, 0], [1, 2], [2, 1]]);
+
+        assert_eq!(player.select_space(&[1, 0]), Ok(Some(0)));
+        assert_eq!(
+            player.density_checks(),
+            vec![[0, 0], [1, 1], [1, 2], [2, 0]]
+        );
+
+        assert_eq!(player.select_space(&[1, 1]), Ok(None));
+        assert_eq!(player.density_checks(), vec![[0, 0], [2, 0]]);
     }
 }
@@ -0,0 +1,260 @@
+use crate::{outcome::ShotOutcome, player::Player, ship::Ship};
+#[cfg(feature = "serde")]
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A record of every ship placement and shot in a game, in the order they happened.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Replay {
+    grid_size: [u8; 2],
+    ship_count: usize,
+    events: Vec<ReplayEvent>,
+}
+
+/// A ship placement or shot made by a player.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReplayEvent {
+    /// The index of the player who made the placement or shot.
+    pub player: usize,
+    /// What the player did.
+    pub action: ReplayAction,
+    /// When the event happened, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// An action recorded in a [`Replay`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReplayAction {
+    /// The player placed a ship in the given position.
+    Placement(Vec<[u8; 2]>),
+    /// The player selected a space on their opponent's grid.
+    Shot {
+        /// The position of the selected space.
+        pos: [u8; 2],
+        /// The outcome of the shot.
+        outcome: ShotOutcome,
+    },
+}
+
+impl Replay {
+    /// Creates a new empty `Replay` for a game with the given grid size and number of ships.
+    pub fn new(grid_size: [u8; 2], ship_count: usize) -> Replay {
+        Replay {
+            grid_size,
+            ship_count,
+            events: vec![],
+        }
+    }
+
+    /// Returns the size of the players' grids.
+    pub fn grid_size(&self) -> [u8; 2] {
+        self.grid_size
+    }
+
+    /// Records that `player` performed `action` now.
+    pub fn record(&mut self, player: usize, action: ReplayAction) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+
+        self.events.push(ReplayEvent {
+            player,
+            action,
+            timestamp,
+        });
+    }
+
+    /// Returns the recorded events.
+    pub fn events(&self) -> &[ReplayEvent] {
+        &self.events
+    }
+
+    /// Returns both players as they were after the first `step` events.
+    ///
+    /// The players' ships are active unless they have been sunk, and their grids show the
+    /// recorded outcomes of the shots made by their opponent, so hits are shown even on a remote
+    /// player whose ships weren't recorded.  Events that couldn't have happened in a game are
+    /// skipped.
+    pub fn players_at(&self, step: usize) -> [Player; 2] {
+        let mut players = [
+            Player::new(self.grid_size, self.ship_count, false),
+            Player::new(self.grid_size, self.ship_count, false),
+        ];
+
+        for event in self.events.iter().take(step).filter(|e| e.player < 2) {
+            match &event.action {
+                ReplayAction::Placement(pos) => {
+                    if let Ok(ship) = Ship::new(pos.clone()) {
+                        let _ = players[event.player].add_ship(
+                            pos[0],
+                            ship.dir(),
+                            ship.len() as u8,
+                            false,
+                        );
+                    }
+                }
+                ReplayAction::Shot { pos, outcome } => {
+                    let opponent = &mut players[1 - event.player];
+
+                    if opponent.set_space_checked(pos, outcome.is_hit()).is_ok()
+                        && outcome.is_sunk()
+                    {
+                        let _ = opponent.sink_ship_if_all_hit(pos);
+                    }
+                }
+            }
+        }
+
+        players
+    }
+
+    /// Writes the replay to `writer` as JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the replay could not be written.
+    #[cfg(feature = "serde")]
+    pub fn save<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Reads a replay from JSON written by [`Replay::save`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the replay could not be read, or if it has an event by a player other
+    /// than the first two, or a placement that isn't a valid ship.
+    #[cfg(feature = "serde")]
+    pub fn load<R: io::Read>(reader: R) -> Result<Replay, serde_json::Error> {
+        use serde::de::Error;
+
+        let replay: Replay = serde_json::from_reader(reader)?;
+
+        for event in &replay.events {
+            if event.player > 1 {
+                return Err(serde_json::Error::custom(format!(
+                    "invalid player {} in replay",
+                    event.player
+                )));
+            } else if let ReplayAction::Placement(pos) = &event.action {
+                Ship::new(pos.clone()).map_err(|e| {
+                    serde_json::Error::custom(format!("invalid ship {:?} in replay: {}", pos, e))
+                })?;
+            }
+        }
+
+        Ok(replay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game::Game,
+        settings::{Difficulty, GameSettings},
+        strategy::DefaultStrategy,
+    };
+
+    #[test]
+    fn players_at() {
+        let settings = GameSettings {
+            seed: Some(7),
+            ..GameSettings::defaults()
+        };
+        let mut game = Game::with_strategies(
+            settings,
+            [
+                Some(Box::new(DefaultStrategy::new(Difficulty::Normal))),
+                Some(Box::new(DefaultStrategy::new(Difficulty::Easy))),
+            ],
+        )
+        .unwrap();
+        game.set_state_active().unwrap();
+
+        for _ in 0..30 {
+            let pos = game.suggested_check();
+            game.select_space(&pos).unwrap();
+            game.switch_active_player();
+        }
+
+        let replay = game.replay();
+        assert_eq!(replay.events().len(), 8 + 30);
+
+        let players = replay.players_at(replay.events().len());
+        let original = [game.active_player(), game.inactive_player()];
+
+        for (player, original) in players.iter().zip(original) {
+            for (space, original) in player.spaces().iter().zip(original.spaces()) {
+                assert_eq!(space.is_hit(), original.is_hit());
+                assert_eq!(space.is_empty(), original.is_empty());
+            }
+
+            for (ship, original) in player.ships().iter().zip(original.ships()) {
+                assert_eq!(ship.pos(), original.pos());
+                assert_eq!(ship.is_sunk(), original.is_sunk());
+            }
+        }
+
+        let players = replay.players_at(8);
+        assert!(players.iter().all(|p| p.unchecked_spaces().len() == 100));
+    }
+
+    #[test]
+    fn remote_outcomes() {
+        // A network game's replay has no placements for the remote player.
+        let mut replay = Replay::new([4, 4], 1);
+        replay.record(0, ReplayAction::Placement(vec![[0, 0], [1, 0]]));
+
+        for (player, pos, outcome) in [
+            (0, [2, 2], ShotOutcome::Hit { ship_index: 0 }),
+            (1, [0, 0], ShotOutcome::Hit { ship_index: 0 }),
+            (0, [3, 2], ShotOutcome::Miss),
+            (
+                1,
+                [1, 0],
+                ShotOutcome::Won {
+                    ship_index: 0,
+                    length: 2,
+                },
+            ),
+        ] {
+            replay.record(player, ReplayAction::Shot { pos, outcome });
+        }
+
+        let players = replay.players_at(replay.events().len());
+        assert!(players[1].ships().is_empty());
+        assert!(players[1].space(&[2, 2]).is_hit());
+        assert!(players[1].space(&[3, 2]).is_empty());
+        assert!(players[0].ships()[0].is_sunk());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn load_invalid() {
+        let mut replay = Replay::new([4, 4], 1);
+        replay.record(0, ReplayAction::Placement(vec![[0, 0], [2, 2]]));
+        let mut saved = vec![];
+        replay.save(&mut saved).unwrap();
+        assert!(Replay::load(saved.as_slice()).is_err());
+
+        let mut replay = Replay::new([4, 4], 1);
+        let shot = ReplayAction::Shot {
+            pos: [0, 0],
+            outcome: ShotOutcome::Miss,
+        };
+        replay.record(2, shot.clone());
+        let mut saved = vec![];
+        replay.save(&mut saved).unwrap();
+        assert!(Replay::load(saved.as_slice()).is_err());
+
+        // Invalid events in a replay that wasn't loaded are skipped.
+        replay.record(0, ReplayAction::Placement(vec![]));
+        replay.record(0, shot);
+        let players = replay.players_at(3);
+        assert!(players[1].space(&[0, 0]).is_empty());
+    }
+}
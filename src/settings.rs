@@ -1,17 +1,384 @@
-pub struct AppSettings {
-    pub space_size: u32,
-}
+use crate::error::BattleshipError;
+use std::cmp;
 
+/// Settings for a game of Battleship.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameSettings {
+    /// The number of columns and rows in each player's grid.
     pub spaces: [u8; 2],
+    /// The lengths of the ships each player places, in placement order.
     pub ships: Vec<u8>,
+    /// Who the players are.
+    pub mode: GameMode,
+    /// How well CPU players choose which spaces to check.
+    pub difficulty: Difficulty,
+    /// How many spaces each player selects per turn.
+    pub shot_rule: ShotRule,
+    /// Whether every player's ships may be placed next to each other.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub adjacency: AdjacencyRule,
+    /// Whether the spaces around a sunk ship are marked as known to be empty, where the adjacency
+    /// rule means they can't contain a ship.
+    ///
+    /// This is ignored in network games, since neither instance knows the positions of the other
+    /// player's ships, so it couldn't know which of its own spaces the other instance marked.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mark_around_sunk: bool,
+    /// The seed for every random choice made during the game, or `None` to choose randomly.
+    ///
+    /// Games with the same settings and seed play out the same way, given the same actions by
+    /// human players.
+    pub seed: Option<u64>,
 }
 
 impl GameSettings {
+    /// Returns the default settings: a 10x10 grid with ships of lengths 2 to 5, against a CPU
+    /// player of normal difficulty, with one shot per turn, ships that may touch, no marking
+    /// around sunk ships, and no seed.
     pub fn defaults() -> GameSettings {
         GameSettings {
             spaces: [10, 10],
             ships: vec![2, 3, 4, 5],
+            mode: GameMode::Cpu,
+            difficulty: Difficulty::Normal,
+            shot_rule: ShotRule::Single,
+            adjacency: AdjacencyRule::Allowed,
+            mark_around_sunk: false,
+            seed: None,
+        }
+    }
+
+    /// Checks that the grid has spaces and the fleet can be placed on it.
+    ///
+    /// Every ship needs a length from 1 to the size of the grid's longest side, and there needs
+    /// to be a way to place the whole fleet that follows the adjacency rule.  Fleets that would
+    /// take too long to search for such a placement are treated as not fitting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the grid has no columns or rows, if the fleet is empty, if a ship's
+    /// length is invalid, or if no placement for the fleet was found.
+    pub fn validate(&self) -> Result<(), BattleshipError> {
+        let longest = cmp::max(self.spaces[0], self.spaces[1]);
+
+        if self.spaces.contains(&0) {
+            return Err(BattleshipError::InvalidGridSize);
+        } else if self.ships.is_empty() {
+            return Err(BattleshipError::EmptyFleet);
+        } else if let Some(&length) = self.ships.iter().find(|&&l| l == 0 || l > longest) {
+            return Err(BattleshipError::InvalidShipLength { length });
+        }
+
+        match search_fleet(self.spaces, &self.ships, self.adjacency) {
+            Some(_) => Ok(()),
+            None => Err(BattleshipError::FleetDoesNotFit),
+        }
+    }
+}
+
+/// Returns the position of each ship in a placement of a fleet with the given lengths that
+/// follows `adjacency`, in the same order as `ships`, or `None` if no placement was found.  Every
+/// ship must have a length of at least 1.
+///
+/// The search always finds the same placement, which packs ships towards the first column, so
+/// it's only a fallback for fleets that are too tight to place randomly.
+pub(crate) fn search_fleet(
+    grid_size: [u8; 2],
+    ships: &[u8],
+    adjacency: AdjacencyRule,
+) -> Option<Vec<Vec<[u8; 2]>>> {
+    // The search places the longest ships first, since they have the fewest positions.
+    let mut order = (0..ships.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| ships[b].cmp(&ships[a]));
+    let lengths = order.iter().map(|&i| ships[i]).collect::<Vec<_>>();
+
+    let [columns, rows] = grid_size;
+    let mut search = FleetSearch {
+        columns: columns as usize,
+        rows: rows as usize,
+        occupied: vec![false; columns as usize * rows as usize],
+        adjacency,
+        placed: Vec::with_capacity(ships.len()),
+        steps: 0,
+    };
+
+    if !search.fits(&lengths, 0) {
+        return None;
+    }
+
+    let mut fleet = vec![vec![]; ships.len()];
+
+    for (i, spaces) in order.into_iter().zip(search.placed) {
+        fleet[i] = spaces.iter().map(|&[x, y]| [x as u8, y as u8]).collect();
+    }
+
+    Some(fleet)
+}
+
+/// A search for a placement of a fleet that follows an adjacency rule.
+struct FleetSearch {
+    columns: usize,
+    rows: usize,
+    /// Whether each space is occupied, indexed by `x * rows + y`.
+    occupied: Vec<bool>,
+    adjacency: AdjacencyRule,
+    /// The spaces of each ship placed so far, in the order they were placed.
+    placed: Vec<Vec<[usize; 2]>>,
+    /// The number of ship positions tried so far.
+    steps: usize,
+}
+
+impl FleetSearch {
+    /// The number of ship positions to try before giving up.
+    const MAX_STEPS: usize = 100_000;
+
+    /// Returns whether ships of the given lengths, longest first, can be added to the occupied
+    /// spaces.
+    ///
+    /// Positions are numbered across both orientations, and `first` is the first position the
+    /// first ship may take, so that ships of the same length are only tried in one order.
+    fn fits(&mut self, lengths: &[u8], first: usize) -> bool {
+        let Some((&length, rest)) = lengths.split_first() else {
+            return true;
+        };
+        let length = length as usize;
+        let area = self.columns * self.rows;
+
+        for position in first..area * 2 {
+            if self.steps == FleetSearch::MAX_STEPS {
+                return false;
+            }
+
+            // A ship of length 1 is the same in both orientations.
+            if length == 1 && position >= area {
+                break;
+            }
+
+            let (x, y) = (position % area / self.rows, position % area % self.rows);
+            let [dx, dy] = match position < area {
+                true => [1, 0],
+                false => [0, 1],
+            };
+
+            if x + dx * (length - 1) >= self.columns || y + dy * (length - 1) >= self.rows {
+                continue;
+            }
+
+            let spaces = (0..length)
+                .map(|i| [x + dx * i, y + dy * i])
+                .collect::<Vec<_>>();
+
+            if spaces.iter().any(|&s| self.is_near_ship(s)) {
+                continue;
+            }
+
+            self.steps += 1;
+            self.set_occupied(&spaces, true);
+            self.placed.push(spaces.clone());
+
+            let next_first = match rest.first() {
+                Some(&next) if next as usize == length => position + 1,
+                _ => 0,
+            };
+
+            if self.fits(rest, next_first) {
+                return true;
+            }
+
+            self.placed.pop();
+            self.set_occupied(&spaces, false);
         }
+
+        false
+    }
+
+    /// Returns whether the given space is occupied, or a ship in it would be too close to an
+    /// occupied space under the adjacency rule.
+    fn is_near_ship(&self, [x, y]: [usize; 2]) -> bool {
+        let occupied = |x: usize, y: usize| self.occupied[x * self.rows + y];
+        let (left, right) = (x > 0, x + 1 < self.columns);
+        let (above, below) = (y > 0, y + 1 < self.rows);
+
+        let orthogonal = || {
+            (left && occupied(x - 1, y))
+                || (right && occupied(x + 1, y))
+                || (above && occupied(x, y - 1))
+                || (below && occupied(x, y + 1))
+        };
+        let diagonal = || {
+            (left && above && occupied(x - 1, y - 1))
+                || (right && above && occupied(x + 1, y - 1))
+                || (left && below && occupied(x - 1, y + 1))
+                || (right && below && occupied(x + 1, y + 1))
+        };
+
+        occupied(x, y)
+            || match self.adjacency {
+                AdjacencyRule::Allowed => false,
+                AdjacencyRule::NoOrthogonalTouch => orthogonal(),
+                AdjacencyRule::NoTouchIncludingDiagonals => orthogonal() || diagonal(),
+            }
+    }
+
+    fn set_occupied(&mut self, spaces: &[[usize; 2]], occupied: bool) {
+        for &[x, y] in spaces {
+            self.occupied[x * self.rows + y] = occupied;
+        }
+    }
+}
+
+/// Who the players of a game are.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameMode {
+    /// A human player against a CPU player.
+    Cpu,
+    /// Two human players taking turns on the same device.
+    Hotseat,
+    /// A human player against a human player on another instance of the game, connected over
+    /// the network.  The host is the first player.
+    Network {
+        /// Whether this instance is hosting the game.
+        host: bool,
+    },
+}
+
+/// How many spaces each player selects per turn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShotRule {
+    /// One space per turn.
+    Single,
+    /// One space per turn for each of the player's ships that hasn't been sunk, with the
+    /// outcomes revealed together at the end of the turn.
+    Salvo,
+    /// The given number of spaces per turn, with the outcomes revealed together at the end of
+    /// the turn.
+    Fixed(u8),
+}
+
+/// Whether ships may be placed in spaces next to each other, which applies to every player.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AdjacencyRule {
+    /// Ships may be placed next to each other, as long as they don't overlap.
+    #[default]
+    Allowed,
+    /// Ships may not be placed side by side or end to end, but may touch diagonally.
+    NoOrthogonalTouch,
+    /// Ships may not touch at all, even diagonally.
+    NoTouchIncludingDiagonals,
+}
+
+impl AdjacencyRule {
+    /// Returns a short description of the rule, for explaining why a ship can't be placed.
+    pub fn description(&self) -> &'static str {
+        match self {
+            AdjacencyRule::Allowed => "ships may touch",
+            AdjacencyRule::NoOrthogonalTouch => "ships may only touch diagonally",
+            AdjacencyRule::NoTouchIncludingDiagonals => "ships may not touch, even diagonally",
+        }
+    }
+
+    /// Returns the word for the rule in the network and external bot protocols.
+    pub(crate) fn keyword(&self) -> &'static str {
+        match self {
+            AdjacencyRule::Allowed => "ALLOWED",
+            AdjacencyRule::NoOrthogonalTouch => "NO-ORTHOGONAL",
+            AdjacencyRule::NoTouchIncludingDiagonals => "NO-TOUCH",
+        }
+    }
+}
+
+/// How well CPU players choose which spaces to check.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Difficulty {
+    /// Checks any unchecked space at random.
+    Easy,
+    /// Checks the spaces around hit ships, or otherwise the spaces that the remaining ships could
+    /// occupy in the most ways.
+    Normal,
+    /// Checks the spaces that the remaining ships could occupy in the most ways, considering hit
+    /// spaces and only checking every other space until a ship is hit.
+    Hard,
+}
+
+impl Difficulty {
+    /// Returns all difficulties, from easiest to hardest.
+    pub fn all() -> [Difficulty; 3] {
+        [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        let settings = |spaces, ships| GameSettings {
+            spaces,
+            ships,
+            adjacency: AdjacencyRule::NoOrthogonalTouch,
+            ..GameSettings::defaults()
+        };
+
+        assert_eq!(GameSettings::defaults().validate(), Ok(()));
+        assert_eq!(settings([10, 10], vec![5, 4, 3, 3, 2]).validate(), Ok(()));
+        assert_eq!(settings([3, 3], vec![1, 1, 1, 1, 1]).validate(), Ok(()));
+        assert_eq!(settings([5, 1], vec![5]).validate(), Ok(()));
+        assert_eq!(settings([8, 12], vec![12, 8, 3, 3]).validate(), Ok(()));
+        assert_eq!(
+            settings([0, 10], vec![2]).validate(),
+            Err(BattleshipError::InvalidGridSize)
+        );
+        assert_eq!(
+            settings([10, 10], vec![]).validate(),
+            Err(BattleshipError::EmptyFleet)
+        );
+        assert_eq!(
+            settings([10, 10], vec![2, 0]).validate(),
+            Err(BattleshipError::InvalidShipLength { length: 0 })
+        );
+        assert_eq!(
+            settings([10, 10], vec![11]).validate(),
+            Err(BattleshipError::InvalidShipLength { length: 11 })
+        );
+        assert_eq!(
+            settings([3, 3], vec![1; 6]).validate(),
+            Err(BattleshipError::FleetDoesNotFit)
+        );
+        assert_eq!(
+            settings([4, 4], vec![4, 4, 4]).validate(),
+            Err(BattleshipError::FleetDoesNotFit)
+        );
+
+        let with_rule = |ships, adjacency| GameSettings {
+            spaces: [3, 3],
+            ships,
+            adjacency,
+            ..GameSettings::defaults()
+        };
+        assert_eq!(
+            with_rule(vec![3, 3, 3], AdjacencyRule::Allowed).validate(),
+            Ok(())
+        );
+        assert_eq!(
+            with_rule(vec![1; 10], AdjacencyRule::Allowed).validate(),
+            Err(BattleshipError::FleetDoesNotFit)
+        );
+        assert_eq!(
+            with_rule(vec![1; 5], AdjacencyRule::NoOrthogonalTouch).validate(),
+            Ok(())
+        );
+        assert_eq!(
+            with_rule(vec![1; 5], AdjacencyRule::NoTouchIncludingDiagonals).validate(),
+            Err(BattleshipError::FleetDoesNotFit)
+        );
+        assert_eq!(
+            with_rule(vec![1; 4], AdjacencyRule::NoTouchIncludingDiagonals).validate(),
+            Ok(())
+        );
     }
 }
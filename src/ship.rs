@@ -1,5 +1,8 @@
-use crate::direction::Direction;
+use crate::{direction::Direction, error::BattleshipError};
 
+/// A ship on a player's grid.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ship {
     state: ShipState,
     position: Vec<[u8; 2]>,
@@ -8,14 +11,19 @@ pub struct Ship {
 
 impl Ship {
     /// Creates a new `Ship` with the given position.
-    pub fn new(pos: Vec<[u8; 2]>) -> Result<Ship, &'static str> {
-        let dir = Direction::from_positions(&pos[1], &pos[0])?;
-
-        Ok(Ship {
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `pos` is empty or does not form a vertical or horizontal line.
+    pub fn new(pos: Vec<[u8; 2]>) -> Result<Ship, BattleshipError> {
+        let mut ship = Ship {
             state: ShipState::Placement,
-            position: pos,
-            dir: dir,
-        })
+            position: vec![],
+            dir: Direction::West,
+        };
+        ship.set_pos(pos)?;
+
+        Ok(ship)
     }
 
     /// Returns the ship's position.
@@ -27,40 +35,37 @@ impl Ship {
     ///
     /// # Errors
     ///
-    /// Returns an error if `pos` does not form a vertical or horizontal line.
-    pub fn set_pos(&mut self, pos: Vec<[u8; 2]>) -> Result<(), &'static str> {
+    /// Returns an error if `pos` is empty or does not form a vertical or horizontal line.
+    pub fn set_pos(&mut self, pos: Vec<[u8; 2]>) -> Result<(), BattleshipError> {
         if pos.is_empty() {
-            Err("tried to set an empty position to a ship")
+            Err(BattleshipError::InvalidShipShape)
         } else if pos.len() == 1 {
             self.position = pos;
 
             Ok(())
         } else {
             let mut valid = true;
-            let dir = Direction::from_positions(&pos[1], &pos[0])?;
+            let dir = Direction::from_positions(&pos[1], &pos[0])
+                .map_err(|_| BattleshipError::InvalidShipShape)?;
 
             for i in 0..pos.len() - 1 {
-                let x_diff = (pos[i][0] as i16 - pos[i + 1][0] as i16).abs() as u8;
-                let y_diff = (pos[i][1] as i16 - pos[i + 1][1] as i16).abs() as u8;
+                let x_diff = pos[i][0].abs_diff(pos[i + 1][0]);
+                let y_diff = pos[i][1].abs_diff(pos[i + 1][1]);
 
                 if x_diff + y_diff != 1 {
                     valid = false;
                     break;
                 }
 
-                let next_dir = Direction::from_positions(&pos[i + 1], &pos[i])?;
-
-                if next_dir != dir {
+                if Direction::from_positions(&pos[i + 1], &pos[i]) != Ok(dir) {
                     valid = false;
                     break;
                 }
             }
 
             if !valid {
-                Err("ship position does not form a continuous line")
+                Err(BattleshipError::InvalidShipShape)
             } else {
-                let dir = Direction::from_positions(&pos[1], &pos[0])?;
-
                 self.position = pos;
                 self.dir = dir;
 
@@ -75,6 +80,8 @@ impl Ship {
     }
 
     /// Returns the ship's length.
+    // A ship always occupies at least one space, so it has no need for `is_empty()`.
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         self.position.len()
     }
@@ -94,9 +101,12 @@ impl Ship {
     /// # Errors
     ///
     /// Returns an error if the ship's state is not `ShipState::Placement`.
-    pub fn set_active(&mut self) -> Result<(), &'static str> {
+    pub fn set_active(&mut self) -> Result<(), BattleshipError> {
         if self.state != ShipState::Placement {
-            Err("tried to set a ship as active that wasn't in placement state")
+            Err(BattleshipError::WrongShipState {
+                expected: ShipState::Placement,
+                actual: self.state,
+            })
         } else {
             self.state = ShipState::Active;
 
@@ -104,6 +114,24 @@ impl Ship {
         }
     }
 
+    /// Returns the ship to the placement state, so that it can be moved again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ship's state is not `ShipState::Active`.
+    pub fn set_placement(&mut self) -> Result<(), BattleshipError> {
+        if self.state != ShipState::Active {
+            Err(BattleshipError::WrongShipState {
+                expected: ShipState::Active,
+                actual: self.state,
+            })
+        } else {
+            self.state = ShipState::Placement;
+
+            Ok(())
+        }
+    }
+
     /// Returns whether the ship has sunk.
     pub fn is_sunk(&self) -> bool {
         self.state == ShipState::Sunk
@@ -114,9 +142,12 @@ impl Ship {
     /// # Errors
     ///
     /// Returns an error if the ship's state is not `ShipState::Active`.
-    pub fn set_sunk(&mut self) -> Result<(), &'static str> {
+    pub fn set_sunk(&mut self) -> Result<(), BattleshipError> {
         if self.state != ShipState::Active {
-            Err("tried to sink a ship that was not active")
+            Err(BattleshipError::WrongShipState {
+                expected: ShipState::Active,
+                actual: self.state,
+            })
         } else {
             self.state = ShipState::Sunk;
 
@@ -125,10 +156,15 @@ impl Ship {
     }
 }
 
-#[derive(PartialEq)]
-enum ShipState {
+/// The state of a [`Ship`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShipState {
+    /// The ship is being positioned during ship placement.
     Placement,
+    /// The ship has been placed and has not been sunk.
     Active,
+    /// Every space the ship occupies has been hit.
     Sunk,
 }
 
@@ -140,6 +176,11 @@ mod tests {
     fn new() {
         let hopefully_ship = Ship::new(vec![[0, 0], [0, 1]]);
         assert!(hopefully_ship.is_ok());
+        assert!(Ship::new(vec![[3, 4]]).is_ok());
+        assert_eq!(
+            Ship::new(vec![]).err(),
+            Some(BattleshipError::InvalidShipShape)
+        );
     }
 
     #[test]
@@ -165,7 +206,10 @@ mod tests {
         assert_eq!(ship.dir(), Direction::North);
 
         assert!(ship.set_pos(vec![[0, 0], [0, 0]]).is_err());
-        assert!(ship.set_pos(vec![[0, 0], [0, 2]]).is_err());
+        assert_eq!(
+            ship.set_pos(vec![[0, 0], [0, 2]]),
+            Err(BattleshipError::InvalidShipShape)
+        );
         assert!(ship.set_pos(vec![]).is_err());
     }
 
@@ -220,6 +264,24 @@ mod tests {
         assert!(ship.set_active().is_err());
     }
 
+    #[test]
+    fn set_placement() {
+        let mut ship = Ship::new(vec![[0, 0], [0, 1]]).unwrap();
+        assert!(ship.set_placement().is_err());
+        assert!(ship.set_active().is_ok());
+        assert!(ship.set_placement().is_ok());
+        assert!(ship.is_placement());
+        assert!(ship.set_active().is_ok());
+        assert!(ship.set_sunk().is_ok());
+        assert_eq!(
+            ship.set_placement(),
+            Err(BattleshipError::WrongShipState {
+                expected: ShipState::Active,
+                actual: ShipState::Sunk,
+            })
+        );
+    }
+
     #[test]
     fn is_sunk() {
         let mut ship = Ship::new(vec![[0, 0], [0, 1]]).unwrap();
@@ -233,7 +295,13 @@ mod tests {
     #[test]
     fn set_sunk() {
         let mut ship = Ship::new(vec![[0, 0], [0, 1]]).unwrap();
-        assert!(ship.set_sunk().is_err());
+        assert_eq!(
+            ship.set_sunk(),
+            Err(BattleshipError::WrongShipState {
+                expected: ShipState::Active,
+                actual: ShipState::Placement,
+            })
+        );
         assert!(ship.set_active().is_ok());
         assert!(ship.set_sunk().is_ok());
         assert!(ship.set_sunk().is_err());
@@ -1,9 +1,15 @@
+use crate::error::BattleshipError;
+
+/// A space on a player's grid.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Space {
     state: SpaceState,
     position: [u8; 2],
 }
 
 impl Space {
+    /// Creates a new unchecked `Space` at the given position.
     pub fn new(pos: [u8; 2]) -> Space {
         Space {
             state: SpaceState::Unchecked,
@@ -11,47 +17,121 @@ impl Space {
         }
     }
 
+    /// Returns unchecked spaces for every position on a grid of the given size, ordered by
+    /// column and then by row.
     pub fn all_grid_spaces(grid_size: &[u8; 2]) -> Vec<Space> {
         (0..grid_size[0])
             .flat_map(|col| (0..grid_size[1]).map(move |row| Space::new([col, row])))
             .collect()
     }
 
+    /// Returns the name of the column at index `x`: `A` to `Z`, followed by `AA`, `AB` and so
+    /// on, like the columns of a spreadsheet.
+    pub fn column_name(x: u8) -> String {
+        let mut name = vec![];
+        let mut n = x as usize + 1;
+
+        while n > 0 {
+            n -= 1;
+            name.push((b'A' + (n % 26) as u8) as char);
+            n /= 26;
+        }
+
+        name.into_iter().rev().collect()
+    }
+
+    /// Returns `pos` as a coordinate made of its column name and row number, such as `B7` for
+    /// `[1, 6]` or `AA1` for `[26, 0]`.
+    pub fn coordinate(pos: &[u8; 2]) -> String {
+        format!("{}{}", Space::column_name(pos[0]), pos[1] as usize + 1)
+    }
+
+    /// Parses a coordinate written by [`Space::coordinate`], or returns `None` if `s` isn't one.
+    pub fn parse_coordinate(s: &str) -> Option<[u8; 2]> {
+        let letters = s.bytes().take_while(u8::is_ascii_uppercase).count();
+        let (column, row) = s.split_at(letters);
+
+        // Three letters are already past the last column a `u8` can index.
+        if letters == 0 || letters > 2 || !row.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        let column = column
+            .bytes()
+            .fold(0, |n, b| n * 26 + (b - b'A') as usize + 1);
+        let row = row.parse::<usize>().ok()?;
+
+        Some([
+            u8::try_from(column - 1).ok()?,
+            u8::try_from(row.checked_sub(1)?).ok()?,
+        ])
+    }
+
     /// Sets this space as having been checked, and whether it was hit.
     ///
     /// # Errors
     ///
     /// Returns an error if the space's state is not `SpaceState::Unchecked`.
-    pub fn set_checked(&mut self, hit: bool) -> Result<(), &'static str> {
+    pub fn set_checked(&mut self, hit: bool) -> Result<(), BattleshipError> {
         if self.state != SpaceState::Unchecked {
-            Err("tried to check an already checked space")
+            Err(BattleshipError::AlreadyChecked)
         } else {
             self.state = SpaceState::Checked(hit);
             Ok(())
         }
     }
 
+    /// Sets this space as known to be empty without having been checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the space's state is not `SpaceState::Unchecked`.
+    pub fn set_known_empty(&mut self) -> Result<(), BattleshipError> {
+        if self.state != SpaceState::Unchecked {
+            Err(BattleshipError::AlreadyChecked)
+        } else {
+            self.state = SpaceState::KnownEmpty;
+            Ok(())
+        }
+    }
+
+    /// Returns whether the space has not been checked, and isn't known to be empty.
     pub fn is_unchecked(&self) -> bool {
         self.state == SpaceState::Unchecked
     }
 
+    /// Returns whether the space has been checked and found to be empty.
     pub fn is_empty(&self) -> bool {
         self.state == SpaceState::Checked(false)
     }
 
+    /// Returns whether the space hasn't been checked, but is known to be empty.
+    pub fn is_known_empty(&self) -> bool {
+        self.state == SpaceState::KnownEmpty
+    }
+
+    /// Returns whether the space has been checked and found to contain a ship.
     pub fn is_hit(&self) -> bool {
         self.state == SpaceState::Checked(true)
     }
 
+    /// Returns the space's position.
     pub fn pos(&self) -> &[u8; 2] {
         &self.position
     }
 }
 
-#[derive(Clone, Copy, PartialEq)]
+/// The state of a [`Space`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SpaceState {
+    /// The space has not been checked.
     Unchecked,
+    /// The space has been checked, and whether it contained a ship.
     Checked(bool),
+    /// The space hasn't been checked, but can't contain a ship because it's next to a sunk ship
+    /// and the game's adjacency rule doesn't allow ships there.
+    KnownEmpty,
 }
 
 #[cfg(test)]
@@ -62,7 +142,10 @@ mod tests {
     fn set_checked() {
         let mut space = Space::new([0, 0]);
         assert!(space.set_checked(false).is_ok());
-        assert!(space.set_checked(false).is_err());
+        assert_eq!(
+            space.set_checked(false),
+            Err(BattleshipError::AlreadyChecked)
+        );
 
         space = Space::new([0, 0]);
         assert!(space.set_checked(true).is_ok());
@@ -93,6 +176,25 @@ mod tests {
         assert!(!space.is_empty());
     }
 
+    #[test]
+    fn set_known_empty() {
+        let mut space = Space::new([0, 0]);
+        assert!(space.set_known_empty().is_ok());
+        assert!(space.is_known_empty());
+        assert!(!space.is_unchecked() && !space.is_empty() && !space.is_hit());
+        assert_eq!(
+            space.set_checked(false),
+            Err(BattleshipError::AlreadyChecked)
+        );
+
+        space = Space::new([0, 0]);
+        assert!(space.set_checked(true).is_ok());
+        assert_eq!(
+            space.set_known_empty(),
+            Err(BattleshipError::AlreadyChecked)
+        );
+    }
+
     #[test]
     fn is_hit() {
         let mut space = Space::new([0, 0]);
@@ -105,6 +207,30 @@ mod tests {
         assert!(!space.is_hit());
     }
 
+    #[test]
+    fn coordinates() {
+        let cases = [
+            ([0, 0], "A1"),
+            ([1, 6], "B7"),
+            ([25, 9], "Z10"),
+            ([26, 0], "AA1"),
+            ([51, 0], "AZ1"),
+            ([52, 0], "BA1"),
+            ([255, 255], "IV256"),
+        ];
+
+        for (pos, coordinate) in cases {
+            assert_eq!(Space::coordinate(&pos), coordinate);
+            assert_eq!(Space::parse_coordinate(coordinate), Some(pos));
+        }
+
+        for invalid in [
+            "", "A", "A0", "a1", "7", "A+1", "A 1", "IW1", "AAA1", "A257",
+        ] {
+            assert_eq!(Space::parse_coordinate(invalid), None);
+        }
+    }
+
     #[test]
     fn pos() {
         let space = Space::new([0, 0]);
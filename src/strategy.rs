@@ -0,0 +1,159 @@
+use crate::{
+    direction::Direction,
+    outcome::ShotOutcome,
+    placement::PlacementOptions,
+    player::Player,
+    settings::{AdjacencyRule, Difficulty},
+    target::TargetView,
+};
+use rand::{seq::SliceRandom, RngCore};
+
+/// A way of placing ships and choosing spaces to check for a computer-controlled player.
+pub trait Strategy {
+    /// Returns the position of the space to check on the opponent's grid, given what the player
+    /// knows about it.
+    ///
+    /// The returned space must be unchecked.
+    fn choose_shot(&mut self, opponent: &TargetView, rng: &mut dyn RngCore) -> [u8; 2];
+
+    /// Returns the head position and direction of each ship in the player's fleet, in the same
+    /// order as `ships`, which contains the length of each ship.
+    ///
+    /// The ships must not overlap or be partially out of bounds, and must follow the game's
+    /// `adjacency` rule.
+    fn place_fleet(
+        &mut self,
+        grid_size: [u8; 2],
+        ships: &[u8],
+        adjacency: AdjacencyRule,
+        rng: &mut dyn RngCore,
+    ) -> Vec<([u8; 2], Direction)>;
+
+    /// Called with the outcome of each of the player's shots once it has been taken.
+    ///
+    /// The default implementation does nothing, since the view of the opponent's grid passed to
+    /// [`Strategy::choose_shot`] already shows every shot that has been taken.
+    fn shot_outcome(&mut self, _pos: [u8; 2], _outcome: ShotOutcome) {}
+}
+
+/// The built-in strategy, which checks spaces according to a [`Difficulty`] and places ships
+/// randomly.
+pub struct DefaultStrategy {
+    difficulty: Difficulty,
+}
+
+impl DefaultStrategy {
+    /// Creates a new `DefaultStrategy` with the given difficulty.
+    pub fn new(difficulty: Difficulty) -> DefaultStrategy {
+        DefaultStrategy { difficulty }
+    }
+}
+
+impl Strategy for DefaultStrategy {
+    fn choose_shot(&mut self, opponent: &TargetView, rng: &mut dyn RngCore) -> [u8; 2] {
+        let positions = match self.difficulty {
+            Difficulty::Easy => opponent.unchecked_spaces(),
+            Difficulty::Normal => opponent.suggested_checks(),
+            Difficulty::Hard => opponent.density_checks(),
+        };
+
+        *positions
+            .choose(rng)
+            .expect("opponent has no unchecked spaces")
+    }
+
+    fn place_fleet(
+        &mut self,
+        grid_size: [u8; 2],
+        ships: &[u8],
+        adjacency: AdjacencyRule,
+        rng: &mut dyn RngCore,
+    ) -> Vec<([u8; 2], Direction)> {
+        let mut player = Player::new(grid_size, ships.len(), true);
+        player.set_adjacency_rule(adjacency);
+        let apart = PlacementOptions {
+            no_touching: true,
+            ..PlacementOptions::default()
+        };
+
+        // Ships are kept apart where there's room, but a fleet that's too tight for that only
+        // needs to follow the rule, and one that's too tight to place randomly is searched for.
+        // If no fleet could be placed, the missing ships are reported by the game.
+        if player.auto_place_fleet(ships, apart, rng).is_err()
+            && player
+                .auto_place_fleet(ships, PlacementOptions::default(), rng)
+                .is_err()
+        {
+            let _ = player.search_place_fleet(ships);
+        }
+
+        player
+            .ships()
+            .iter()
+            .map(|ship| (ship.pos()[0], ship.dir()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn place_fleet() {
+        // Each rule with a roomy fleet, and with a fleet that only fits if ships touch as much as
+        // the rule allows.
+        let cases = [
+            (AdjacencyRule::Allowed, [10, 10], vec![2, 3, 4, 5]),
+            (AdjacencyRule::Allowed, [3, 3], vec![3, 3, 3]),
+            (AdjacencyRule::NoOrthogonalTouch, [10, 10], vec![2, 3, 4, 5]),
+            (AdjacencyRule::NoOrthogonalTouch, [3, 3], vec![1; 5]),
+            (
+                AdjacencyRule::NoTouchIncludingDiagonals,
+                [10, 10],
+                vec![2, 3, 4, 5],
+            ),
+            (AdjacencyRule::NoTouchIncludingDiagonals, [3, 3], vec![1; 4]),
+        ];
+
+        for (adjacency, grid_size, ships) in cases {
+            let fleet = DefaultStrategy::new(Difficulty::Normal).place_fleet(
+                grid_size,
+                &ships,
+                adjacency,
+                &mut StdRng::seed_from_u64(0),
+            );
+            let mut player = Player::new(grid_size, ships.len(), true);
+            player.set_adjacency_rule(adjacency);
+
+            assert_eq!(fleet.len(), ships.len());
+
+            for (&(head, dir), &len) in fleet.iter().zip(&ships) {
+                assert!(player.add_ship(head, dir, len, false).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn choose_shot() {
+        let mut player = Player::new([3, 3], 1, true);
+        assert!(player.add_ship([0, 0], Direction::West, 3, false).is_ok());
+
+        for pos in player
+            .unchecked_spaces()
+            .into_iter()
+            .filter(|p| p != &[2, 2])
+        {
+            assert!(player.select_space(&pos).is_ok());
+        }
+
+        for difficulty in Difficulty::all() {
+            let mut strategy = DefaultStrategy::new(difficulty);
+            assert_eq!(
+                strategy.choose_shot(&player.target_view(), &mut StdRng::seed_from_u64(0)),
+                [2, 2]
+            );
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use crate::{
+    direction::Direction,
+    error::BattleshipError,
+    outcome::ShotOutcome,
+    player::{movement, ship_position},
+    space::Space,
+};
+
+/// What a player knows about their opponent's grid from the shots they've taken: which spaces
+/// have been checked and whether they were hit, and the lengths and positions of the ships that
+/// have been sunk.
+///
+/// Unlike the opponent's [`Player`], it doesn't reveal where their other ships are, so it's what
+/// a [`Strategy`] chooses its shots from.
+///
+/// [`Player`]: crate::Player
+/// [`Strategy`]: crate::Strategy
+#[derive(Clone)]
+pub struct TargetView {
+    grid_size: [u8; 2],
+    spaces: Vec<Space>,
+    lengths: Vec<u8>,
+    hits: Vec<Vec<[u8; 2]>>,
+    sunk: Vec<bool>,
+}
+
+impl TargetView {
+    /// Creates a view of an unchecked grid of the given size, hiding a fleet with the given ship
+    /// lengths in placement order.
+    pub fn new(grid_size: [u8; 2], lengths: Vec<u8>) -> TargetView {
+        TargetView::from_spaces(grid_size, Space::all_grid_spaces(&grid_size), lengths)
+    }
+
+    /// Creates a view of a grid whose spaces are already known, before any of its hit spaces are
+    /// known to belong to a ship.
+    pub(crate) fn from_spaces(
+        grid_size: [u8; 2],
+        spaces: Vec<Space>,
+        lengths: Vec<u8>,
+    ) -> TargetView {
+        TargetView {
+            grid_size,
+            spaces,
+            hits: vec![vec![]; lengths.len()],
+            sunk: vec![false; lengths.len()],
+            lengths,
+        }
+    }
+
+    /// Records the outcome of a shot at `pos`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no space exists at `pos`, or if the space at `pos` was already checked.
+    pub fn record_shot(
+        &mut self,
+        pos: &[u8; 2],
+        outcome: ShotOutcome,
+    ) -> Result<(), BattleshipError> {
+        if !self.valid_space(pos) {
+            return Err(BattleshipError::OutOfBounds);
+        }
+
+        let space_index = self.space_index(pos);
+        self.spaces[space_index].set_checked(outcome.is_hit())?;
+        self.record_outcome(pos, outcome);
+
+        Ok(())
+    }
+
+    /// Records which ship the hit space at `pos` belongs to, and whether it was sunk, according
+    /// to `outcome`.
+    ///
+    /// Outcomes that refer to a ship that isn't in the fleet, which could be reported by a remote
+    /// player, are ignored.
+    pub(crate) fn record_outcome(&mut self, pos: &[u8; 2], outcome: ShotOutcome) {
+        if let Some(ship_index) = outcome.ship_index().filter(|&i| i < self.lengths.len()) {
+            if !self.hits[ship_index].contains(pos) {
+                self.hits[ship_index].push(*pos);
+            }
+
+            if outcome.is_sunk() {
+                self.sunk[ship_index] = true;
+            }
+        }
+    }
+
+    /// Returns the number of columns and rows in the grid.
+    pub fn grid_size(&self) -> [u8; 2] {
+        self.grid_size
+    }
+
+    /// Gets a reference to the spaces.
+    pub fn spaces(&self) -> &[Space] {
+        &self.spaces
+    }
+
+    /// Gets a reference to the space with the given position.
+    pub fn space(&self, pos: &[u8; 2]) -> &Space {
+        self.spaces.get(self.space_index(pos)).unwrap()
+    }
+
+    /// Returns the length of each ship in the opponent's fleet, in placement order.
+    pub fn ship_lengths(&self) -> &[u8] {
+        &self.lengths
+    }
+
+    /// Returns the positions of the ship at `ship_index` if it has been sunk.
+    pub fn sunk_ship(&self, ship_index: usize) -> Option<&[[u8; 2]]> {
+        match self.sunk.get(ship_index) {
+            Some(true) => Some(&self.hits[ship_index]),
+            _ => None,
+        }
+    }
+
+    /// Returns the lengths of the ships that haven't been sunk, in placement order.
+    pub fn remaining_lengths(&self) -> Vec<u8> {
+        self.lengths
+            .iter()
+            .zip(&self.sunk)
+            .filter(|(_, &sunk)| !sunk)
+            .map(|(&length, _)| length)
+            .collect()
+    }
+
+    /// Returns whether the space at `pos` was hit, and belongs to a ship that hasn't been sunk.
+    fn is_unsunk_hit(&self, pos: &[u8; 2]) -> bool {
+        self.space(pos).is_hit()
+            && !self
+                .hits
+                .iter()
+                .zip(&self.sunk)
+                .any(|(hits, &sunk)| sunk && hits.contains(pos))
+    }
+
+    /// Returns suggestions for the best spaces to check based on the hit spaces and sunk ships.
+    ///
+    /// This is intended for use in cases where the active player is computer-controlled, to
+    /// determine the space they check.  However, it could also be used to suggest a space that a
+    /// human player could check.
+    pub fn suggested_checks(&self) -> Vec<[u8; 2]> {
+        let mut select = vec![];
+        let directions = Direction::all();
+        let hit_spaces = self
+            .spaces
+            .iter()
+            .filter(|s| self.is_unsunk_hit(s.pos()))
+            .collect::<Vec<&Space>>();
+
+        // Check for a line of hit spaces.
+        for space in &hit_spaces {
+            for direction in &directions {
+                let unchecked = self.find_unchecked_space(space.pos(), *direction, true);
+
+                if let Some(pos) = unchecked {
+                    if !select.contains(&pos) {
+                        select.push(pos);
+                    }
+                }
+            }
+        }
+
+        // If a hit space was found, but no hit spaces next to it, look for unchecked spaces next
+        // to it.
+        if !hit_spaces.is_empty() && select.is_empty() {
+            for direction in &directions {
+                let unchecked = self.find_unchecked_space(hit_spaces[0].pos(), *direction, false);
+
+                if let Some(pos) = unchecked {
+                    select.push(pos);
+                }
+            }
+        }
+
+        // If no candidates have been found yet, add the unchecked spaces that the remaining ships
+        // could occupy in the most ways.
+        if select.is_empty() {
+            let density = self.placement_density();
+            let max_density = density.iter().copied().max().unwrap_or(0);
+
+            select = self
+                .spaces
+                .iter()
+                .zip(&density)
+                .filter(|(space, &d)| {
+                    space.is_unchecked() && (d == max_density || max_density == 0)
+                })
+                .map(|(space, _)| *space.pos())
+                .collect::<Vec<[u8; 2]>>();
+        }
+
+        select
+    }
+
+    /// Returns suggestions for the best spaces to check based on where the remaining ships could
+    /// be positioned.
+    ///
+    /// While any ship has been hit but not sunk, only ship positions that include hit spaces are
+    /// considered, weighted by the number of hit spaces they include.  Otherwise, only spaces on
+    /// a checkerboard pattern spaced by the shortest remaining ship's length are suggested, since
+    /// every remaining ship must occupy one of them.
+    pub fn density_checks(&self) -> Vec<[u8; 2]> {
+        let hunting = !self.spaces.iter().any(|s| self.is_unsunk_hit(s.pos()));
+        let density = self.weighted_placement_density(!hunting);
+        let parity = self.remaining_lengths().into_iter().min().unwrap_or(1) as usize;
+        let on_parity =
+            |pos: &[u8; 2]| !hunting || (pos[0] as usize + pos[1] as usize).is_multiple_of(parity);
+        let max_density = self
+            .spaces
+            .iter()
+            .zip(&density)
+            .filter(|(space, _)| on_parity(space.pos()))
+            .map(|(_, &d)| d)
+            .max()
+            .unwrap_or(0);
+
+        let select = self
+            .spaces
+            .iter()
+            .zip(&density)
+            .filter(|(space, &d)| {
+                space.is_unchecked() && on_parity(space.pos()) && d == max_density && d > 0
+            })
+            .map(|(space, _)| *space.pos())
+            .collect::<Vec<[u8; 2]>>();
+
+        if select.is_empty() {
+            self.unchecked_spaces()
+        } else {
+            select
+        }
+    }
+
+    /// Returns the positions of all unchecked spaces.
+    pub fn unchecked_spaces(&self) -> Vec<[u8; 2]> {
+        self.spaces
+            .iter()
+            .filter(|space| space.is_unchecked())
+            .map(|space| *space.pos())
+            .collect()
+    }
+
+    /// Returns, for each space, the number of ways the remaining ships could be positioned to
+    /// occupy it, in the same order as `spaces()`.
+    ///
+    /// A ship position is only counted if none of its spaces are known to be empty or to be
+    /// occupied by a sunk ship.  Checked spaces always have a density of zero.
+    pub fn placement_density(&self) -> Vec<u32> {
+        self.weighted_placement_density(false)
+    }
+
+    /// Returns the placement density of each space.
+    ///
+    /// If `hits_only` is true, only ship positions that include hit spaces of ships that haven't
+    /// been sunk are counted, and each is counted once for every such hit space it includes.
+    fn weighted_placement_density(&self, hits_only: bool) -> Vec<u32> {
+        let mut density = vec![0; self.spaces.len()];
+
+        for length in self.remaining_lengths() {
+            for space in &self.spaces {
+                // North and west positions cover every line of spaces; south and east would only
+                // count the same positions again.
+                for direction in [Direction::North, Direction::West] {
+                    let ship_pos = ship_position(self.grid_size, *space.pos(), direction, length);
+
+                    if let Some(ship_pos) =
+                        ship_pos.filter(|p| p.iter().all(|p| self.may_hide_ship(p)))
+                    {
+                        let weight = match hits_only {
+                            true => {
+                                ship_pos.iter().filter(|p| self.space(p).is_hit()).count() as u32
+                            }
+                            false => 1,
+                        };
+
+                        for pos in ship_pos.iter().filter(|p| self.space(p).is_unchecked()) {
+                            density[self.space_index(pos)] += weight;
+                        }
+                    }
+                }
+            }
+        }
+
+        density
+    }
+
+    /// Returns whether the space at `pos` could be occupied by a ship that hasn't been sunk.
+    fn may_hide_ship(&self, pos: &[u8; 2]) -> bool {
+        self.space(pos).is_unchecked() || self.is_unsunk_hit(pos)
+    }
+
+    /// Finds the first non-hit, unchecked space in a `direction` from `pos`.
+    /// Can also make sure the space is at the end of a `line` of hit spaces.
+    /// Returns `None` if the first non-hit space has been checked or if a grid
+    /// boundary is reached.
+    fn find_unchecked_space(
+        &self,
+        pos: &[u8; 2],
+        direction: Direction,
+        check_for_line: bool,
+    ) -> Option<[u8; 2]> {
+        let mut check_pos = movement(self.grid_size, pos, direction);
+
+        while let Some(next_pos) = check_pos {
+            let next_space = self.space(&next_pos);
+
+            match next_space.is_hit() {
+                true => check_pos = movement(self.grid_size, &next_pos, direction),
+                false => {
+                    if !next_space.is_unchecked() {
+                        check_pos = None;
+                    }
+                    break;
+                }
+            };
+        }
+
+        if check_for_line && check_pos.is_some() {
+            let unchecked = check_pos.unwrap();
+            let opposite_dir = direction.opposite();
+            let prev_pos = movement(self.grid_size, &unchecked, opposite_dir).unwrap();
+
+            if &prev_pos == pos {
+                check_pos = None;
+            }
+        }
+
+        check_pos
+    }
+
+    /// Returns whether the given position is valid.
+    fn valid_space(&self, pos: &[u8; 2]) -> bool {
+        pos[0] < self.grid_size[0] && pos[1] < self.grid_size[1]
+    }
+
+    /// Calculates the index of the given position in the spaces vector.
+    fn space_index(&self, pos: &[u8; 2]) -> usize {
+        self.grid_size[1] as usize * pos[0] as usize + pos[1] as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_shot() {
+        let mut view = TargetView::new([3, 3], vec![2, 1]);
+        assert_eq!(
+            view.record_shot(&[0, 0], ShotOutcome::Hit { ship_index: 0 }),
+            Ok(())
+        );
+        assert_eq!(
+            view.record_shot(&[0, 0], ShotOutcome::Miss),
+            Err(BattleshipError::AlreadyChecked)
+        );
+        assert_eq!(
+            view.record_shot(&[3, 0], ShotOutcome::Miss),
+            Err(BattleshipError::OutOfBounds)
+        );
+        assert_eq!(view.sunk_ship(0), None);
+        assert_eq!(view.remaining_lengths(), vec![2, 1]);
+
+        let sunk = ShotOutcome::Sunk {
+            ship_index: 0,
+            length: 2,
+        };
+        assert_eq!(view.record_shot(&[1, 0], sunk), Ok(()));
+        assert_eq!(view.sunk_ship(0), Some(&[[0, 0], [1, 0]][..]));
+        assert_eq!(view.remaining_lengths(), vec![1]);
+
+        // The sunk ship's spaces can't hide the remaining ship.
+        assert_eq!(view.placement_density()[..4], [0, 2, 2, 0]);
+    }
+}
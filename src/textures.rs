@@ -0,0 +1,66 @@
+use piston_window::*;
+use std::{env::current_exe, path::PathBuf};
+
+/// The textures used to draw the game, loaded from the assets directory.
+pub struct Textures {
+    /// Unchecked, empty and hit grid spaces, followed by a space occupied by a ship.
+    pub spaces: Vec<G2dTexture>,
+    pub grid_cursor: G2dTexture,
+    pub player_text: [G2dTexture; 2],
+    /// The "game over" and "wins" text.
+    pub game_over_text: [G2dTexture; 2],
+}
+
+impl Textures {
+    /// Loads the textures for use in `window`.
+    pub fn load(window: &mut PistonWindow) -> Textures {
+        let assets_dir = get_assets_dir(current_exe().unwrap()).unwrap();
+        let images_dir: PathBuf = assets_dir.join("images");
+        let mut get_texture = |file: &str| get_texture(window, images_dir.join(file));
+
+        let mut spaces = vec![];
+
+        for state in 0..3 {
+            spaces.push(get_texture(&format!("gridspace-{}.png", state)));
+        }
+
+        spaces.push(get_texture("shipspace.png"));
+
+        let grid_cursor = get_texture("grid-cursor.png");
+
+        let player_text = [get_texture("player-1.png"), get_texture("player-2.png")];
+        let game_over_text = [get_texture("game-over.png"), get_texture("wins.png")];
+
+        Textures {
+            spaces,
+            grid_cursor,
+            player_text,
+            game_over_text,
+        }
+    }
+}
+
+/// Returns the texture from the file at the given path.
+fn get_texture(window: &mut PistonWindow, path: PathBuf) -> G2dTexture {
+    Texture::from_path(
+        &mut window.create_texture_context(),
+        path,
+        Flip::None,
+        &TextureSettings::new(),
+    )
+    .unwrap()
+}
+
+/// Returns the assets directory, if it could be found.
+fn get_assets_dir(mut dir: PathBuf) -> Result<PathBuf, &'static str> {
+    let mut result = None;
+
+    while dir.pop() {
+        if dir.join("assets").exists() {
+            result = Some(dir.join("assets"));
+            break;
+        }
+    }
+
+    result.ok_or("could not find assets directory")
+}
@@ -0,0 +1,171 @@
+use crate::{
+    app::{AppSettings, HEADER_HEIGHT},
+    textures::Textures,
+};
+use piston_window::*;
+use rust_battleship::{Player, Replay, ReplayAction};
+
+/// Shows a recorded game one placement or shot at a time, with both players' grids side by side.
+pub struct ReplayViewer {
+    window: PistonWindow,
+    space_size: u32,
+    replay: Replay,
+    step: usize,
+    players: [Player; 2],
+    grid_size: [u32; 2],
+}
+
+impl ReplayViewer {
+    pub fn new(settings: &AppSettings, replay: Replay) -> ReplayViewer {
+        // Leave a space's width around the grids, and room for the progress bar below them.
+        let [columns, rows] = replay.grid_size().map(|n| n as u32);
+        let space_size = settings.fitted_space_size([columns * 2 + 3, rows + 2]);
+        let grid_size = [columns * space_size, rows * space_size];
+        let window_size = [
+            grid_size[0] * 2 + space_size * 3,
+            HEADER_HEIGHT + grid_size[1] + space_size * 2,
+        ];
+
+        let window: PistonWindow = WindowSettings::new("Battleship Replay", window_size)
+            .exit_on_esc(true)
+            .resizable(false)
+            .build()
+            .unwrap();
+        let players = replay.players_at(0);
+
+        ReplayViewer {
+            window,
+            space_size,
+            replay,
+            step: 0,
+            players,
+            grid_size,
+        }
+    }
+
+    pub fn init(&mut self) {
+        self.window.set_ups(60);
+        self.window.set_max_fps(60);
+
+        let textures = Textures::load(&mut self.window);
+
+        while let Some(e) = self.window.next() {
+            if let Some(p) = e.press_args() {
+                match p {
+                    Button::Keyboard(keyboard::Key::Left) => {
+                        self.go_to(self.step.saturating_sub(1))
+                    }
+                    Button::Keyboard(keyboard::Key::Right)
+                    | Button::Keyboard(keyboard::Key::Space)
+                    | Button::Keyboard(keyboard::Key::Return) => self.go_to(self.step + 1),
+                    Button::Keyboard(keyboard::Key::Home) => self.go_to(0),
+                    Button::Keyboard(keyboard::Key::End) => self.go_to(usize::MAX),
+                    _ => {}
+                }
+            }
+
+            if e.render_args().is_some() {
+                let space_size = self.space_size;
+                let grid_size = self.grid_size;
+                let players = &self.players;
+                let event_count = self.replay.events().len();
+                let step = self.step;
+                let last_event = match step {
+                    0 => None,
+                    _ => self.replay.events().get(step - 1),
+                };
+                let window_size = self.window.size();
+                let texture_scale = space_size as f64 / textures.spaces[0].get_size().0 as f64;
+
+                self.window.draw_2d(&e, |c, g, _| {
+                    clear([0.6, 0.6, 1.0, 1.0], g);
+
+                    for (i, player) in players.iter().enumerate() {
+                        let grid_pos = [
+                            space_size + (grid_size[0] + space_size) * i as u32,
+                            HEADER_HEIGHT,
+                        ];
+                        let space_transform = |pos: &[u8; 2]| {
+                            c.transform
+                                .trans(
+                                    (grid_pos[0] + space_size * pos[0] as u32) as f64,
+                                    (grid_pos[1] + space_size * pos[1] as u32) as f64,
+                                )
+                                .scale(texture_scale, texture_scale)
+                        };
+
+                        // Player text image, centred above their grid
+                        let player_text_size = textures.player_text[i].get_size();
+                        image(
+                            &textures.player_text[i],
+                            c.transform.trans(
+                                grid_pos[0] as f64
+                                    + (grid_size[0] as f64 - player_text_size.0 as f64) / 2.0,
+                                HEADER_HEIGHT as f64 / 3.0,
+                            ),
+                            g,
+                        );
+
+                        // Grid spaces, showing the positions of ships that haven't been hit.
+                        for space in player.spaces() {
+                            let texture = if space.is_unchecked() {
+                                match player.ship_is_in_space(space.pos()) {
+                                    true => 3,
+                                    false => 0,
+                                }
+                            } else if space.is_empty() {
+                                1
+                            } else {
+                                2
+                            };
+                            image(&textures.spaces[texture], space_transform(space.pos()), g);
+                        }
+
+                        // Mark the space or ship affected by the latest event.
+                        if let Some(event) = last_event {
+                            let marked = match &event.action {
+                                ReplayAction::Placement(pos) if event.player == i => pos.clone(),
+                                ReplayAction::Shot { pos, .. } if event.player != i => vec![*pos],
+                                _ => vec![],
+                            };
+
+                            for pos in &marked {
+                                image(&textures.grid_cursor, space_transform(pos), g);
+                            }
+                        }
+                    }
+
+                    // Progress through the replay
+                    let progress = match event_count {
+                        0 => 1.0,
+                        _ => step as f64 / event_count as f64,
+                    };
+                    let bar = [
+                        space_size as f64,
+                        window_size.height - space_size as f64 * 1.5,
+                        window_size.width - space_size as f64 * 2.0,
+                        space_size as f64 / 2.0,
+                    ];
+                    rectangle([0.0, 0.0, 0.0, 0.2], bar, c.transform, g);
+                    rectangle(
+                        [0.0, 0.0, 0.0, 0.8],
+                        [bar[0], bar[1], bar[2] * progress, bar[3]],
+                        c.transform,
+                        g,
+                    );
+                });
+            }
+        }
+    }
+
+    /// Shows the players as they were after the first `step` events, or after the last event if
+    /// there are fewer than `step` events.
+    fn go_to(&mut self, step: usize) {
+        let step = step.min(self.replay.events().len());
+
+        if step != self.step {
+            self.step = step;
+            self.players = self.replay.players_at(step);
+        }
+    }
+}